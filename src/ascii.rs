@@ -1,38 +1,51 @@
 use colored::*;
 use crate::colors::get_theme_colors;
 
+/// Returns the colored ASCII logo for a distribution id, one string per line.
+/// Lines contain ANSI color codes; unknown ids get a generic logo.
 pub fn get_ascii_art(distro: &str) -> Vec<String> {
     let theme = get_theme_colors(distro);
     
-    match distro {
-        "ubuntu" => ubuntu_ascii(&theme),
-        "debian" => debian_ascii(&theme),
-        "arch" => arch_ascii(&theme),
-        "fedora" => fedora_ascii(&theme),
-        "centos" => centos_ascii(&theme),
-        "opensuse" => opensuse_ascii(&theme),
-        "gentoo" => gentoo_ascii(&theme),
-        "alpine" => alpine_ascii(&theme),
-        "manjaro" => manjaro_ascii(&theme),
-        "mint" => mint_ascii(&theme),
-        "freebsd" => freebsd_ascii(&theme),
-        "macos" => macos_ascii(&theme),
-        "void" => void_ascii(&theme),
-        "kali" => kali_ascii(&theme),
-        "elementary" => elementary_ascii(&theme),
-        "pop" => pop_ascii(&theme),
-        "nixos" => nixos_ascii(&theme),
-        "slackware" => slackware_ascii(&theme),
-        "rhel" => rhel_ascii(&theme),
-        "openbsd" => openbsd_ascii(&theme),
-        "netbsd" => netbsd_ascii(&theme),
-        "endeavouros" => endeavouros_ascii(&theme),
-        "zorin" => zorin_ascii(&theme),
-        "deepin" => deepin_ascii(&theme),
-        "solus" => solus_ascii(&theme),
-        "garuda" => garuda_ascii(&theme),
-        _ => default_ascii(&theme),
-    }
+    distro_ascii(distro, &theme).unwrap_or_else(|| default_ascii(&theme))
+}
+
+/// Whether `distro` has its own logo rather than the generic one.
+pub fn has_ascii_art(distro: &str) -> bool {
+    distro_ascii(distro, &get_theme_colors(distro)).is_some()
+}
+
+fn distro_ascii(distro: &str, theme: &crate::colors::ThemeColors) -> Option<Vec<String>> {
+    let art = match distro {
+        "ubuntu" => ubuntu_ascii(theme),
+        "debian" => debian_ascii(theme),
+        "arch" => arch_ascii(theme),
+        "fedora" => fedora_ascii(theme),
+        "centos" => centos_ascii(theme),
+        "opensuse" => opensuse_ascii(theme),
+        "gentoo" => gentoo_ascii(theme),
+        "alpine" => alpine_ascii(theme),
+        "manjaro" => manjaro_ascii(theme),
+        "mint" => mint_ascii(theme),
+        "freebsd" => freebsd_ascii(theme),
+        "macos" => macos_ascii(theme),
+        "void" => void_ascii(theme),
+        "kali" => kali_ascii(theme),
+        "elementary" => elementary_ascii(theme),
+        "pop" => pop_ascii(theme),
+        "nixos" => nixos_ascii(theme),
+        "slackware" => slackware_ascii(theme),
+        "rhel" => rhel_ascii(theme),
+        "openbsd" => openbsd_ascii(theme),
+        "netbsd" => netbsd_ascii(theme),
+        "endeavouros" => endeavouros_ascii(theme),
+        "zorin" => zorin_ascii(theme),
+        "deepin" => deepin_ascii(theme),
+        "solus" => solus_ascii(theme),
+        "garuda" => garuda_ascii(theme),
+        _ => return None,
+    };
+    
+    Some(art)
 }
 
 fn ubuntu_ascii(theme: &crate::colors::ThemeColors) -> Vec<String> {
@@ -1,12 +1,20 @@
 use colored::Color;
 
+/// Color scheme matching a distribution's branding.
+#[derive(Debug, Clone, Copy)]
 pub struct ThemeColors {
+    /// Logo and label color.
     pub primary: Color,
+    /// Secondary logo color and label separators.
     pub secondary: Color,
+    /// Color for information values.
     pub text: Color,
     pub accent: Color,
 }
 
+/// Returns the theme for a distribution id as produced by
+/// [`detect_distribution`](crate::distro::detect_distribution). Unknown ids
+/// get a neutral cyan/blue theme.
 pub fn get_theme_colors(distro: &str) -> ThemeColors {
     match distro {
         "ubuntu" => ThemeColors {
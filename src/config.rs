@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::modules::{self, CpuConfig, DiskConfig, MemoryConfig, NetworkConfig, PackagesConfig};
+
+/// Module names shown by the built-in layouts, in display order. `--all`
+/// shows every module in the registry.
+pub const MINIMAL_MODULES: &[&str] = &["title", "os", "kernel", "cpu", "memory"];
+pub const DEFAULT_MODULES: &[&str] = &[
+    "title", "os", "model", "virtualization", "kernel", "uptime", "shell", "de", "wm", "theme", "icons", "font", "cursor",
+    "terminal", "terminal_font", "cpu", "gpu", "display", "memory", "swap", "disk", "network", "battery", "packages",
+];
+
+#[derive(Debug)]
+pub struct ConfigError(String);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Configuration error: {}", self.0)
+    }
+}
+
+impl Error for ConfigError {}
+
+/// User configuration, read from `$XDG_CONFIG_HOME/rustch/config.toml`.
+/// Command line flags take precedence over the values set here.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Disable ASCII art by default
+    pub no_art: bool,
+    /// Disable colors by default
+    pub no_color: bool,
+    /// Distribution id to use instead of detecting it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distro: Option<String>,
+    /// Modules to show, in display order
+    pub modules: Vec<ModuleConfig>,
+    /// Options for the `packages` module
+    pub packages: PackagesConfig,
+    /// Options for the `cpu` module
+    pub cpu: CpuConfig,
+    /// Options for the `memory` module
+    pub memory: MemoryConfig,
+    /// Options for the `disk` module
+    pub disk: DiskConfig,
+    /// Options for the `network` module
+    pub network: NetworkConfig,
+}
+
+/// One entry of the `[[modules]]` list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModuleConfig {
+    pub name: String,
+    /// Label shown before the value; defaults to the module's own label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            no_art: false,
+            no_color: false,
+            distro: None,
+            modules: layout(DEFAULT_MODULES),
+            packages: PackagesConfig::default(),
+            cpu: CpuConfig::default(),
+            memory: MemoryConfig::default(),
+            disk: DiskConfig::default(),
+            network: NetworkConfig::default(),
+        }
+    }
+}
+
+impl ModuleConfig {
+    pub fn new(name: &str) -> Self {
+        ModuleConfig {
+            name: name.to_string(),
+            label: modules::find(name).map(|module| module.label().to_string()),
+        }
+    }
+
+    /// The label to display, falling back to the module's default.
+    pub fn label(&self) -> &str {
+        match &self.label {
+            Some(label) => label,
+            None => modules::find(&self.name).map_or("Unknown", |module| module.label()),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the configuration from `path`, or from the default location when
+    /// `path` is `None`. A missing default file yields the built-in config; a
+    /// missing explicit file is an error.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let (path, required) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) if !required && !path.exists() => return Ok(Config::default()),
+            Err(e) => return Err(ConfigError(format!("{}: {}", path.display(), e))),
+        };
+
+        Config::parse(&content).map_err(|e| ConfigError(format!("{}: {}", path.display(), e.0)))
+    }
+
+    /// Parses a configuration from TOML text.
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        let config: Config = toml::from_str(content).map_err(|e| ConfigError(e.to_string()))?;
+
+        for module in &config.modules {
+            if modules::find(&module.name).is_none() {
+                return Err(ConfigError(format!(
+                    "unknown module '{}' (available: {})",
+                    module.name,
+                    modules::names().join(", ")
+                )));
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Renders the configuration as TOML, suitable for `config.toml`.
+    pub fn to_toml(&self) -> Result<String, ConfigError> {
+        toml::to_string_pretty(self).map_err(|e| ConfigError(e.to_string()))
+    }
+}
+
+/// Builds a module list with default labels.
+pub fn layout(names: &[&str]) -> Vec<ModuleConfig> {
+    names.iter().map(|name| ModuleConfig::new(name)).collect()
+}
+
+/// `$XDG_CONFIG_HOME/rustch/config.toml`, or `~/.config/rustch/config.toml`
+/// when `XDG_CONFIG_HOME` is unset.
+pub fn default_path() -> Option<PathBuf> {
+    let config_home = match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var("HOME").ok()?).join(".config"),
+    };
+
+    Some(config_home.join("rustch").join("config.toml"))
+}
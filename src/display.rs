@@ -0,0 +1,176 @@
+//! Text and JSON output of collected information.
+
+use colored::*;
+use serde_json::{Map, Value};
+
+use crate::colors::ThemeColors;
+use crate::config::{Config, ModuleConfig};
+use crate::modules::Line;
+use crate::system::SystemInfo;
+
+/// Renders the modules of `layout` as "Label: value" lines. Modules that are
+/// unavailable are left out, as are undetected ones unless `show_missing` is set.
+pub fn get_info_lines(info: &SystemInfo, layout: &[ModuleConfig], config: &Config, show_missing: bool) -> Vec<String> {
+    let mut info_lines = Vec::new();
+    
+    for module in layout {
+        // Unavailable modules are never shown
+        let Some(entry) = info.entry(&module.name) else {
+            continue;
+        };
+        
+        let lines = match &entry.value {
+            Some(value) => value.render(config),
+            None if show_missing => vec![Line::new("Unknown")],
+            None => continue,
+        };
+        
+        // The WM line is redundant when the DE already names it
+        if module.name == "wm" && !show_missing {
+            let de = info.get("de").map(|de| de.render(config));
+            if de.as_ref() == Some(&lines) {
+                continue;
+            }
+        }
+        
+        for line in lines {
+            match line.key {
+                // An empty label shows the value alone, as for the title
+                _ if module.label().is_empty() => info_lines.push(line.value),
+                Some(key) => info_lines.push(format!("{} ({}): {}", module.label(), key, line.value)),
+                None => info_lines.push(format!("{}: {}", module.label(), line.value)),
+            }
+        }
+    }
+    
+    info_lines
+}
+
+/// Builds the JSON object for `--format json`: the detected distro id plus the
+/// requested modules. Modules that could not be detected are `null`.
+pub fn build_json(distro: &str, info: &SystemInfo, layout: &[ModuleConfig]) -> Value {
+    let mut object = Map::new();
+    object.insert("distro".to_string(), Value::String(distro.to_string()));
+    
+    for module in layout {
+        let value = info.get(&module.name).map_or(Value::Null, |value| value.to_json());
+        object.insert(module.name.clone(), value);
+    }
+    
+    Value::Object(object)
+}
+
+/// Lays the info lines out next to the ASCII art, coloring both unless
+/// `no_color` is set.
+pub fn render_output(ascii_lines: &[String], info_lines: &[String], theme: &ThemeColors, no_color: bool) -> String {
+    let mut output = String::new();
+    
+    // Calculate the maximum width of ASCII art for proper alignment
+    let max_ascii_width = ascii_lines
+        .iter()
+        .map(|line| strip_ansi_codes(line).chars().count())
+        .max()
+        .unwrap_or(0);
+    
+    let max_lines = ascii_lines.len().max(info_lines.len());
+    let padding_base = if max_ascii_width > 0 { 4 } else { 0 }; // Base padding between ASCII and info
+    
+    for i in 0..max_lines {
+        let ascii_part = if i < ascii_lines.len() {
+            if no_color {
+                strip_ansi_codes(&ascii_lines[i])
+            } else {
+                ascii_lines[i].clone()
+            }
+        } else {
+            String::new()
+        };
+        
+        let info_part = if i < info_lines.len() {
+            if no_color {
+                // Values such as usage bars may carry their own colors
+                strip_ansi_codes(&info_lines[i])
+            } else {
+                format_info_line(&info_lines[i], theme)
+            }
+        } else {
+            String::new()
+        };
+        
+        // Calculate dynamic padding
+        let ascii_display_width = strip_ansi_codes(&ascii_part).chars().count();
+        let padding_needed = if max_ascii_width > ascii_display_width {
+            max_ascii_width - ascii_display_width + padding_base
+        } else {
+            padding_base
+        };
+        
+        let padding = " ".repeat(padding_needed);
+        
+        // Add the line
+        if !ascii_part.is_empty() || !info_part.is_empty() {
+            output.push_str(&ascii_part);
+            if !info_part.is_empty() {
+                output.push_str(&padding);
+                output.push_str(&info_part);
+            }
+            output.push('\n');
+        }
+    }
+    
+    output
+}
+
+fn format_info_line(line: &str, theme: &ThemeColors) -> String {
+    if let Some(colon_pos) = line.find(':') {
+        let (label, value) = line.split_at(colon_pos);
+        let value_part = &value[1..]; // Remove the colon
+        
+        // Handle special cases for better formatting
+        let formatted_value = if value_part.trim().is_empty() || value_part.trim() == "Unknown" {
+            " N/A".dimmed().to_string()
+        } else {
+            format!(" {}", value_part.color(theme.text))
+        };
+        
+        format!("{}{}{}", 
+            label.color(theme.primary).bold(),
+            ":".color(theme.secondary),
+            formatted_value
+        )
+    } else if let Some((user, host)) = line.split_once('@') {
+        // The user@hostname title
+        format!("{}{}{}",
+            user.color(theme.primary).bold(),
+            "@".color(theme.secondary),
+            host.color(theme.primary).bold()
+        )
+    } else {
+        line.color(theme.text).to_string()
+    }
+}
+
+/// Removes ANSI escape sequences, leaving the visible text.
+pub fn strip_ansi_codes(input: &str) -> String {
+    let mut result = String::new();
+    let mut chars = input.chars().peekable();
+    
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            // Handle ANSI escape sequences
+            if chars.peek() == Some(&'[') {
+                chars.next(); // consume '['
+                // Skip until we find a letter (the command)
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() || c == 'm' {
+                        break;
+                    }
+                }
+            }
+        } else {
+            result.push(ch);
+        }
+    }
+    
+    result
+}
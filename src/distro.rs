@@ -1,7 +1,15 @@
-use std::fs;
+use serde::Serialize;
 use std::error::Error;
 use std::fmt;
+use std::path::Path;
 
+use crate::ascii::has_ascii_art;
+use crate::source::{RealSource, SystemSource};
+
+/// os-release locations, in lookup order (see os-release(5)).
+const OS_RELEASE_PATHS: &[&str] = &["/etc/os-release", "/usr/lib/os-release"];
+
+/// Error returned when the distribution cannot be detected.
 #[derive(Debug)]
 pub struct DistroError(String);
 
@@ -13,16 +21,144 @@ impl fmt::Display for DistroError {
 
 impl Error for DistroError {}
 
-pub fn detect_distribution() -> Result<String, DistroError> {
-    // Try /etc/os-release first (most modern systems)
-    if let Ok(content) = fs::read_to_string("/etc/os-release") {
-        if let Some(distro) = parse_os_release(&content) {
-            return Ok(distro);
+/// Fields of `/etc/os-release`. Values are unquoted and unescaped; fields
+/// missing from the file are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OsRelease {
+    pub id: Option<String>,
+    /// Distributions this one is derived from, closest first
+    pub id_like: Vec<String>,
+    pub name: Option<String>,
+    pub pretty_name: Option<String>,
+    pub version_id: Option<String>,
+    pub version_codename: Option<String>,
+    pub variant_id: Option<String>,
+    pub build_id: Option<String>,
+    pub home_url: Option<String>,
+    pub logo: Option<String>,
+    pub ansi_color: Option<String>,
+}
+
+impl OsRelease {
+    /// Reads `/etc/os-release`, falling back to `/usr/lib/os-release`.
+    pub fn load() -> Option<Self> {
+        OsRelease::load_from(&RealSource::new())
+    }
+
+    /// Like [`load`](OsRelease::load), but for the system described by
+    /// `source`.
+    pub fn load_from(source: &dyn SystemSource) -> Option<Self> {
+        OS_RELEASE_PATHS
+            .iter()
+            .find_map(|path| source.read_to_string(Path::new(path)))
+            .map(|content| OsRelease::parse(&content))
+    }
+
+    /// Parses os-release content. Values may be unquoted, single-quoted or
+    /// double-quoted, with shell-style backslash escapes.
+    pub fn parse(content: &str) -> Self {
+        let mut os_release = OsRelease::default();
+        
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            
+            let Some((key, raw_value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = unquote(raw_value);
+            
+            let field = match key.trim() {
+                "ID" => &mut os_release.id,
+                "ID_LIKE" => {
+                    os_release.id_like = value.split_whitespace().map(str::to_string).collect();
+                    continue;
+                }
+                "NAME" => &mut os_release.name,
+                "PRETTY_NAME" => &mut os_release.pretty_name,
+                "VERSION_ID" => &mut os_release.version_id,
+                "VERSION_CODENAME" => &mut os_release.version_codename,
+                "VARIANT_ID" => &mut os_release.variant_id,
+                "BUILD_ID" => &mut os_release.build_id,
+                "HOME_URL" => &mut os_release.home_url,
+                "LOGO" => &mut os_release.logo,
+                "ANSI_COLOR" => &mut os_release.ansi_color,
+                _ => continue,
+            };
+            *field = Some(value).filter(|value| !value.is_empty());
         }
+        
+        os_release
+    }
+
+    /// The distribution id to use for the logo and theme: `ID` if rustch
+    /// has a logo for it, otherwise the first `ID_LIKE` entry that has one,
+    /// otherwise `ID` as is.
+    pub fn logo_id(&self) -> Option<String> {
+        let id = self.id.as_deref().map(normalize_distro_name);
+        
+        id.iter()
+            .cloned()
+            .chain(self.id_like.iter().map(|like| normalize_distro_name(like)))
+            .find(|candidate| has_ascii_art(candidate))
+            .or(id)
+    }
+}
+
+/// Removes os-release quoting the way a shell would: `"..."` and `'...'`
+/// groups are unquoted and backslash escapes are resolved.
+fn unquote(raw: &str) -> String {
+    let mut value = String::new();
+    let mut chars = raw.trim().chars();
+    let mut quote = None;
+    
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some('\''), c) => value.push(c),
+            // Inside double quotes only shell special characters are escaped
+            (Some(_), '\\') => match chars.next() {
+                Some(escaped @ ('$' | '"' | '\\' | '`')) => value.push(escaped),
+                Some(other) => {
+                    value.push('\\');
+                    value.push(other);
+                }
+                None => value.push('\\'),
+            },
+            (None, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    value.push(escaped);
+                }
+            }
+            // Unquoted whitespace ends the value
+            (None, c) if c.is_whitespace() => break,
+            (_, c) => value.push(c),
+        }
+    }
+    
+    value
+}
+
+/// Detects the running distribution and returns its normalized id, such as
+/// `"ubuntu"`, `"arch"` or `"macos"`: os-release's `ID`, whether or not
+/// rustch has a logo for it. Returns `"unknown"` when nothing matches.
+pub fn detect_distribution() -> Result<String, DistroError> {
+    detect_distribution_from(&RealSource::new())
+}
+
+/// Like [`detect_distribution`], but for the system described by `source`,
+/// such as an unpacked image, a chroot or a test fixture.
+pub fn detect_distribution_from(source: &dyn SystemSource) -> Result<String, DistroError> {
+    // Try os-release first (most modern systems)
+    if let Some(id) = OsRelease::load_from(source).and_then(|os_release| os_release.id) {
+        return Ok(normalize_distro_name(&id));
     }
     
     // Try /etc/lsb-release
-    if let Ok(content) = fs::read_to_string("/etc/lsb-release") {
+    if let Some(content) = source.read_to_string(Path::new("/etc/lsb-release")) {
         if let Some(distro) = parse_lsb_release(&content) {
             return Ok(distro);
         }
@@ -43,42 +179,45 @@ pub fn detect_distribution() -> Result<String, DistroError> {
     ];
     
     for (file, distro) in distro_files {
-        if fs::metadata(file).is_ok() {
+        if source.exists(Path::new(file)) {
             return Ok(distro.to_string());
         }
     }
     
-    // Check for macOS
-    if cfg!(target_os = "macos") {
-        return Ok("macos".to_string());
-    }
-    
-    // Check for FreeBSD
-    if cfg!(target_os = "freebsd") {
-        return Ok("freebsd".to_string());
-    }
-    
-    // Check for OpenBSD
-    if cfg!(target_os = "openbsd") {
-        return Ok("openbsd".to_string());
+    // The remaining checks describe the running system
+    if !source.is_live() {
+        return Ok("unknown".to_string());
     }
     
-    // Check for NetBSD
-    if cfg!(target_os = "netbsd") {
-        return Ok("netbsd".to_string());
+    // macOS and the BSDs without os-release
+    match source.os() {
+        "macos" => Ok("macos".to_string()),
+        "freebsd" => Ok("freebsd".to_string()),
+        "openbsd" => Ok("openbsd".to_string()),
+        "netbsd" => Ok("netbsd".to_string()),
+        _ => Ok("unknown".to_string()),
     }
-    
-    Ok("unknown".to_string())
 }
 
-fn parse_os_release(content: &str) -> Option<String> {
-    for line in content.lines() {
-        if line.starts_with("ID=") {
-            let id = line.trim_start_matches("ID=").trim_matches('"');
-            return Some(normalize_distro_name(id));
-        }
+/// The distribution whose logo and colors stand for `distro` on the running
+/// system: `distro` itself when rustch has a logo for it, otherwise the
+/// closest `ID_LIKE` distribution that has one.
+pub fn logo_distribution(distro: &str) -> String {
+    logo_distribution_from(&RealSource::new(), distro)
+}
+
+/// Like [`logo_distribution`], but for the system described by `source`.
+pub fn logo_distribution_from(source: &dyn SystemSource, distro: &str) -> String {
+    if has_ascii_art(distro) {
+        return distro.to_string();
     }
-    None
+
+    // ID_LIKE only describes the detected distribution, not one forced
+    // with --distro
+    OsRelease::load_from(source)
+        .filter(|os_release| os_release.id.as_deref().map(normalize_distro_name).as_deref() == Some(distro))
+        .and_then(|os_release| os_release.logo_id())
+        .unwrap_or_else(|| distro.to_string())
 }
 
 fn parse_lsb_release(content: &str) -> Option<String> {
@@ -0,0 +1,381 @@
+use colored::*;
+use std::time::Duration;
+
+use crate::modules::{
+    Battery, BatteryStatus, Bios, Cpu, CpuConfig, Disk, Font, Gpu, InterfaceKind, Memory, Mode, Monitor, NetworkInterface,
+    Packages, PackagesDisplay, Product, Shell, Title, WindowManager,
+};
+
+/// Formats the title as `"user@hostname"`, or just the hostname when the
+/// user is unknown.
+pub fn format_title(title: &Title) -> String {
+    match &title.user {
+        Some(user) => format!("{}@{}", user, title.hostname),
+        None => title.hostname.clone(),
+    }
+}
+
+/// Formats a machine or board as `"Dell Inc. XPS 15 9520 (A05)"`, leaving
+/// out the vendor when the name already starts with it.
+pub fn format_product(product: &Product) -> String {
+    let mut text = match &product.vendor {
+        Some(vendor) if !starts_with_word(&product.name, vendor) => format!("{} {}", vendor, product.name),
+        _ => product.name.clone(),
+    };
+    if let Some(version) = &product.version {
+        text.push_str(&format!(" ({})", version));
+    }
+    text
+}
+
+/// Formats firmware as `"American Megatrends Inc. 1.40 (11/25/2021)"`.
+pub fn format_bios(bios: &Bios) -> String {
+    let mut text = match &bios.vendor {
+        Some(vendor) => format!("{} {}", vendor, bios.version),
+        None => bios.version.clone(),
+    };
+    if let Some(date) = &bios.date {
+        text.push_str(&format!(" ({})", date));
+    }
+    text
+}
+
+/// Whether `name` starts with the first word of `vendor`, ignoring case:
+/// "ASUSTeK COMPUTER INC." and "ASUS ROG ..." do not match, but "Dell Inc."
+/// and "Dell XPS" do.
+fn starts_with_word(name: &str, vendor: &str) -> bool {
+    let word = vendor.split_whitespace().next().unwrap_or_default();
+    name.split_whitespace()
+        .next()
+        .is_some_and(|first| !word.is_empty() && first.eq_ignore_ascii_case(word))
+}
+
+/// Formats an uptime as `"2d 3h 4m"`, dropping leading zero units.
+pub fn format_uptime(uptime: Duration) -> String {
+    let uptime_seconds = uptime.as_secs();
+    let days = uptime_seconds / 86400;
+    let hours = (uptime_seconds % 86400) / 3600;
+    let minutes = (uptime_seconds % 3600) / 60;
+
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Formats package counts as `"1834 (dpkg)"` for a single manager; several
+/// managers are shown as a total, a per-manager breakdown, or both. Returns
+/// `None` when no manager was found.
+pub fn format_packages(packages: &Packages, display: PackagesDisplay) -> Option<String> {
+    let breakdown = packages
+        .0
+        .iter()
+        .map(|package| format!("{} ({})", package.count, package.manager))
+        .collect::<Vec<_>>()
+        .join(", ");
+    
+    match (packages.0.len(), display) {
+        (0, _) => None,
+        (1, _) | (_, PackagesDisplay::Breakdown) => Some(breakdown),
+        (_, PackagesDisplay::Total) => Some(format!("{} total", packages.total())),
+        (_, PackagesDisplay::Both) => Some(format!("{} total: {}", packages.total(), breakdown)),
+    }
+}
+
+pub fn format_shell(shell: &Shell) -> String {
+    match &shell.version {
+        Some(version) => format!("{} {}", shell.name, version),
+        None => shell.name.clone(),
+    }
+}
+
+pub fn format_window_manager(wm: &WindowManager) -> String {
+    match &wm.version {
+        Some(version) => format!("{} {}", wm.name, version),
+        None => wm.name.clone(),
+    }
+}
+
+/// Formats a CPU as `"AMD Ryzen 7 5800X (8 cores, 16 threads) @ 4.85 GHz -
+/// 45.0°C"`, with the parts enabled in `config`. Hybrid CPUs show their core
+/// kinds as `"6P + 8E cores"`.
+pub fn format_cpu(cpu: &Cpu, config: &CpuConfig) -> String {
+    let mut text = cpu.brand.clone();
+    
+    if config.topology {
+        let mut counts = Vec::new();
+        if let Some(sockets) = cpu.sockets.filter(|&sockets| sockets > 1) {
+            counts.push(plural(sockets, "socket"));
+        }
+        match (cpu.performance_cores, cpu.efficiency_cores, cpu.physical_cores) {
+            (Some(performance), Some(efficiency), _) if config.hybrid => {
+                counts.push(format!("{}P + {}E cores", performance, efficiency));
+            }
+            (_, _, Some(cores)) => counts.push(plural(cores, "core")),
+            _ => {}
+        }
+        counts.push(plural(cpu.logical_cores, "thread"));
+        if let Some(limit) = cpu.cpu_limit {
+            let limit = (limit * 100.0).round() / 100.0;
+            counts.push(format!("limited to {} {}", limit, if limit == 1.0 { "CPU" } else { "CPUs" }));
+        }
+        text.push_str(&format!(" ({})", counts.join(", ")));
+    }
+    
+    if let Some(mhz) = cpu.max_frequency_mhz.filter(|_| config.frequency) {
+        text.push_str(&format!(" @ {:.2} GHz", mhz as f64 / 1000.0));
+    }
+    
+    if let Some(celsius) = cpu.temperature_celsius.filter(|_| config.temperature) {
+        text.push_str(&format!(" - {:.1}°C", celsius));
+    }
+    
+    text
+}
+
+/// Formats a GPU as `"AMD Radeon RX 6800 XT (16.0GB, amdgpu)"`, with the
+/// VRAM size and driver when known.
+pub fn format_gpu(gpu: &Gpu) -> String {
+    let name = match &gpu.name {
+        Some(name) if name.starts_with(&gpu.vendor) => name.clone(),
+        Some(name) => format!("{} {}", gpu.vendor, name),
+        None => format!("{} Device {}", gpu.vendor, gpu.device_id),
+    };
+    
+    let mut details = Vec::new();
+    if let Some(vram_bytes) = gpu.vram_bytes {
+        let (value, unit) = format_bytes(vram_bytes);
+        details.push(format!("{:.1}{}", value, unit));
+    }
+    if let Some(driver) = &gpu.driver {
+        details.push(driver.clone());
+    }
+    
+    if details.is_empty() {
+        name
+    } else {
+        format!("{} ({})", name, details.join(", "))
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Formats a monitor as `"Dell U2720Q (27\") - 3840x2160 @ 60 Hz"`, adding
+/// the native resolution when the current one differs.
+pub fn format_monitor(monitor: &Monitor) -> String {
+    // Model names often repeat the maker: "DELL U2720Q"
+    let name = match (&monitor.manufacturer, &monitor.model) {
+        (Some(manufacturer), Some(model))
+            if !model.to_lowercase().starts_with(&manufacturer.to_lowercase()) =>
+        {
+            Some(format!("{} {}", manufacturer, model))
+        }
+        (_, Some(model)) => Some(model.clone()),
+        (manufacturer, None) => manufacturer.clone(),
+    };
+    let diagonal = match (monitor.width_mm, monitor.height_mm) {
+        (Some(width), Some(height)) => Some(((width * width + height * height) as f64).sqrt() / 25.4),
+        _ => None,
+    };
+
+    let mut parts = Vec::new();
+    match (name, diagonal) {
+        (Some(name), Some(diagonal)) => parts.push(format!("{} ({:.0}\")", name, diagonal)),
+        (Some(name), None) => parts.push(name),
+        (None, Some(diagonal)) => parts.push(format!("{:.0}\"", diagonal)),
+        (None, None) => {}
+    }
+
+    match (monitor.current, monitor.preferred) {
+        (Some(current), Some(preferred)) if (current.width, current.height) != (preferred.width, preferred.height) => {
+            parts.push(format!("{} (native {}x{})", format_mode(current), preferred.width, preferred.height))
+        }
+        (Some(mode), _) | (None, Some(mode)) => parts.push(format_mode(mode)),
+        (None, None) => {}
+    }
+
+    parts.join(" - ")
+}
+
+fn format_mode(mode: Mode) -> String {
+    match mode.refresh_hz {
+        Some(refresh) => format!("{}x{} @ {:.0} Hz", mode.width, mode.height, refresh),
+        None => format!("{}x{}", mode.width, mode.height),
+    }
+}
+
+/// Formats a font as `"Cantarell (11pt)"`.
+pub fn format_font(font: &Font) -> String {
+    match font.size {
+        Some(size) => format!("{} ({}pt)", font.family, size),
+        None => font.family.clone(),
+    }
+}
+
+/// Formats a setting of GTK and Qt applications. Both are shown, as
+/// `"Breeze [Qt], Adwaita [GTK]"`, only when they differ.
+pub fn format_toolkits(gtk: Option<String>, qt: Option<String>) -> String {
+    match (gtk, qt) {
+        (Some(gtk), Some(qt)) if gtk != qt => format!("{} [Qt], {} [GTK]", qt, gtk),
+        (Some(value), _) | (None, Some(value)) => value,
+        (None, None) => String::new(),
+    }
+}
+
+/// Formats memory usage as `"3.2GB / 15.5GB (20%)"`. Under a cgroup limit
+/// the host's RAM follows: `"700.0MB / 2.0GB (34%) - cgroup limit (host:
+/// 15.6GB)"`.
+pub fn format_memory(memory: &Memory) -> String {
+    let usage = format_usage(memory.used_bytes, memory.total_bytes);
+    match memory.host_total_bytes {
+        Some(host_total_bytes) => {
+            let (value, unit) = format_bytes(host_total_bytes);
+            format!("{} - cgroup limit (host: {:.1}{})", usage, value, unit)
+        }
+        None => usage,
+    }
+}
+
+/// Formats swap usage like memory, with the compression ratio of zram
+/// swap: `"512.0MB / 8.0GB (6%) - 3.2x compressed"`. No swap at all is
+/// shown as `"Disabled"`.
+pub fn format_swap(used_bytes: u64, total_bytes: u64, compression_ratio: Option<f64>) -> String {
+    if total_bytes == 0 {
+        return "Disabled".to_string();
+    }
+
+    match compression_ratio {
+        Some(ratio) => format!("{} - {:.1}x compressed", format_usage(used_bytes, total_bytes), ratio),
+        None => format_usage(used_bytes, total_bytes),
+    }
+}
+
+/// Formats disk usage as `"120.5GB / 465.8GB (25%) - ext4"`, followed by a
+/// usage bar when `bar` is set.
+pub fn format_disk(disk: &Disk, bar: bool) -> String {
+    let mut text = format_usage(disk.used_bytes, disk.total_bytes);
+    if let Some(filesystem) = &disk.filesystem {
+        text.push_str(&format!(" - {}", filesystem));
+    }
+    if bar {
+        text.push(' ');
+        text.push_str(&usage_bar(percentage(disk.used_bytes, disk.total_bytes)));
+    }
+    text
+}
+
+/// Formats an interface as `"192.168.1.42/24, 2001:db8::42/64 (Wired, 1
+/// Gbps)"`; without addresses only the part in parentheses is shown.
+pub fn format_network(interface: &NetworkInterface) -> String {
+    let mut details = match interface.kind {
+        InterfaceKind::Wired => "Wired",
+        InterfaceKind::Wireless => "Wireless",
+        InterfaceKind::Virtual => "Virtual",
+    }
+    .to_string();
+    match interface.speed_mbps {
+        Some(speed) if speed >= 1000 && speed % 1000 == 0 => details.push_str(&format!(", {} Gbps", speed / 1000)),
+        Some(speed) if speed >= 1000 => details.push_str(&format!(", {:.1} Gbps", speed as f64 / 1000.0)),
+        Some(speed) => details.push_str(&format!(", {} Mbps", speed)),
+        None => {}
+    }
+
+    let addresses: Vec<&str> = interface.ipv4.iter().chain(&interface.ipv6).map(String::as_str).collect();
+    if addresses.is_empty() {
+        details
+    } else {
+        format!("{} ({})", addresses.join(", "), details)
+    }
+}
+
+/// Formats a battery as `"85% (Discharging, 3h 12m remaining) - 92% health"`,
+/// leaving out whatever the battery does not report.
+pub fn format_battery(battery: &Battery) -> String {
+    let status = match battery.status {
+        BatteryStatus::Charging => Some("Charging"),
+        BatteryStatus::Discharging => Some("Discharging"),
+        BatteryStatus::Full => Some("Full"),
+        BatteryStatus::NotCharging => Some("Not charging"),
+        BatteryStatus::Unknown => None,
+    };
+    let remaining = battery
+        .time_remaining_secs
+        .map(|secs| format!("{} remaining", format_uptime(Duration::from_secs(secs))));
+    let details = status.map(str::to_string).into_iter().chain(remaining).collect::<Vec<_>>().join(", ");
+
+    let mut text = battery.percentage.map(|percentage| format!("{}%", percentage)).unwrap_or_default();
+    if !details.is_empty() {
+        if text.is_empty() {
+            text = details;
+        } else {
+            text.push_str(&format!(" ({})", details));
+        }
+    }
+    if let Some(health) = battery.health_percentage {
+        if text.is_empty() {
+            text = format!("{}% health", health);
+        } else {
+            text.push_str(&format!(" - {}% health", health));
+        }
+    }
+    text
+}
+
+/// A ten-cell bar such as `[███░░░░░░░]`, green below 70%, yellow below 90%
+/// and red above.
+pub fn usage_bar(percentage: u32) -> String {
+    const CELLS: usize = 10;
+    let filled = ((percentage as usize + 5) / 10).min(CELLS);
+    let color = match percentage {
+        0..=69 => Color::Green,
+        70..=89 => Color::Yellow,
+        _ => Color::Red,
+    };
+
+    format!(
+        "[{}{}]",
+        "█".repeat(filled).color(color),
+        "░".repeat(CELLS - filled).dimmed()
+    )
+}
+
+/// Formats used and total sizes as `"3.2GB / 15.5GB (20%)"`.
+fn format_usage(used_bytes: u64, total_bytes: u64) -> String {
+    // Convert to appropriate units
+    let (used_val, used_unit) = format_bytes(used_bytes);
+    let (total_val, total_unit) = format_bytes(total_bytes);
+
+    format!("{:.1}{} / {:.1}{} ({}%)",
+        used_val, used_unit, total_val, total_unit, percentage(used_bytes, total_bytes))
+}
+
+fn percentage(used: u64, total: u64) -> u32 {
+    if total > 0 {
+        (used as f64 / total as f64 * 100.0) as u32
+    } else {
+        0
+    }
+}
+
+/// Scales a byte count to the largest binary unit below 1024.
+pub fn format_bytes(bytes: u64) -> (f64, &'static str) {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+
+    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+
+    (size, UNITS[unit_index])
+}
@@ -0,0 +1,37 @@
+//! System information collectors and distribution logos behind the `rustch`
+//! command line tool.
+//!
+//! ```no_run
+//! use rustch::{detect_distribution, get_ascii_art, logo_distribution, SystemInfo};
+//!
+//! let distro = detect_distribution().unwrap_or_else(|_| "unknown".to_string());
+//! let info = SystemInfo::new();
+//!
+//! for line in get_ascii_art(&logo_distribution(&distro)) {
+//!     println!("{}", line);
+//! }
+//! if let Some(memory) = info.get_as::<rustch::modules::Memory>("memory") {
+//!     println!("{} of {} bytes used", memory.used_bytes, memory.total_bytes);
+//! }
+//! ```
+//!
+//! Information is gathered by the [`modules`] registry: each module collects
+//! a typed value that renders itself as text (via [`format`]) or JSON.
+
+pub mod ascii;
+pub mod colors;
+pub mod config;
+pub mod display;
+pub mod distro;
+pub mod format;
+pub mod modules;
+pub mod source;
+mod sqlite;
+pub mod system;
+
+pub use ascii::get_ascii_art;
+pub use colors::{get_theme_colors, ThemeColors};
+pub use distro::{detect_distribution, logo_distribution, DistroError, OsRelease};
+pub use modules::{Context, InfoModule};
+pub use source::{FixtureSource, InterfaceAddress, RealSource, SystemSource};
+pub use system::SystemInfo;
@@ -1,16 +1,20 @@
-use clap::Parser;
-use colored::*;
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
 use std::process;
 
-mod ascii;
-mod colors;
-mod distro;
-mod system;
+use rustch::config::{self, Config, MINIMAL_MODULES};
+use rustch::display::{build_json, get_info_lines, render_output};
+use rustch::modules::{self, Context, InfoModule};
+use rustch::distro::{detect_distribution_from, logo_distribution_from};
+use rustch::{get_ascii_art, get_theme_colors, RealSource, SystemInfo};
 
-use ascii::get_ascii_art;
-use colors::get_theme_colors;
-use distro::detect_distribution;
-use system::SystemInfo;
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// ASCII art and colored "Label: value" lines
+    Text,
+    /// A single JSON object, without art or colors
+    Json,
+}
 
 #[derive(Parser)]
 #[command(name = "rustch")]
@@ -36,210 +40,154 @@ struct Cli {
     /// Show all available information
     #[arg(short = 'A', long)]
     all: bool,
+    
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    
+    /// Read configuration from this file instead of the default location
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+    
+    /// Print the built-in configuration and exit
+    #[arg(long)]
+    print_default_config: bool,
+    
+    /// Show only these modules, in this order (comma-separated)
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    modules: Option<Vec<String>>,
+    
+    /// List the available modules and exit
+    #[arg(long)]
+    list_modules: bool,
+    
+    /// Inspect the system installed under this directory (an unpacked image
+    /// or chroot) instead of the running one
+    #[arg(long, value_name = "DIR")]
+    root: Option<PathBuf>,
 }
 
 fn main() {
     let cli = Cli::parse();
     
-    // Detect the distribution
-    let distro = if let Some(forced_distro) = cli.distro {
-        forced_distro
-    } else {
-        match detect_distribution() {
-            Ok(d) => d,
+    if cli.print_default_config {
+        match Config::default().to_toml() {
+            Ok(toml) => print!("{}", toml),
             Err(e) => {
-                eprintln!("Error detecting distribution: {}", e);
-                "unknown".to_string()
+                eprintln!("{}", e);
+                process::exit(1);
             }
         }
-    };
+        return;
+    }
     
-    // Get system information
-    let system_info = match SystemInfo::new() {
-        Ok(info) => info,
+    let config = match Config::load(cli.config.as_deref()) {
+        Ok(config) => config,
         Err(e) => {
-            eprintln!("Error gathering system information: {}", e);
+            eprintln!("{}", e);
             process::exit(1);
         }
     };
     
-    // Get ASCII art and colors
-    let ascii_lines = if !cli.no_art {
-        get_ascii_art(&distro)
+    let root = cli.root.clone().unwrap_or_else(|| PathBuf::from("/"));
+    if !root.is_dir() {
+        eprintln!("Root {} is not a directory", root.display());
+        process::exit(1);
+    }
+    
+    if cli.list_modules {
+        let ctx = Context::new(config).with_root(&root);
+        let width = modules::names().iter().map(|name| name.len()).max().unwrap_or(0);
+        for module in modules::registry() {
+            let status = if module.is_available(&ctx) { "" } else { " (unavailable)" };
+            println!("{:<width$}  {}{}", module.name(), module.label(), status, width = width);
+        }
+        return;
+    }
+    
+    // Command line flags override the config file
+    let no_art = cli.no_art || config.no_art;
+    let no_color = cli.no_color || config.no_color;
+    
+    // Detect the distribution
+    let distro = if let Some(forced_distro) = cli.distro.or_else(|| config.distro.clone()) {
+        forced_distro
     } else {
-        vec![]
+        match detect_distribution_from(&RealSource::with_root(&root)) {
+            Ok(d) => d,
+            Err(e) => {
+                eprintln!("Error detecting distribution: {}", e);
+                "unknown".to_string()
+            }
+        }
     };
     
-    let theme = get_theme_colors(&distro);
-    
-    // Prepare info lines based on mode
-    let info_lines = if cli.minimal {
-        get_minimal_info(&system_info)
+    // --modules, --minimal and --all replace the configured layout; the
+    // latter two also show fields that could not be detected
+    let (layout, show_missing) = if let Some(names) = &cli.modules {
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        (config::layout(&names), false)
+    } else if cli.minimal {
+        (config::layout(MINIMAL_MODULES), true)
     } else if cli.all {
-        get_all_info(&system_info)
+        (config::layout(&modules::names()), true)
     } else {
-        get_default_info(&system_info)
+        (config.modules.clone(), false)
     };
     
-    // Display the output
-    display_output(&ascii_lines, &info_lines, &theme, cli.no_color);
-}
-
-fn get_minimal_info(info: &SystemInfo) -> Vec<String> {
-    vec![
-        format!("OS: {}", info.os_name),
-        format!("Host: {}", info.hostname),
-        format!("Kernel: {}", info.kernel_version),
-        format!("CPU: {}", info.cpu),
-        format!("Memory: {}", info.memory),
-    ]
-}
-
-fn get_default_info(info: &SystemInfo) -> Vec<String> {
-    let mut info_lines = vec![
-        format!("OS: {}", info.os_name),
-        format!("Host: {}", info.hostname),
-        format!("Kernel: {}", info.kernel_version),
-        format!("Uptime: {}", info.uptime),
-        format!("Shell: {}", info.shell),
-    ];
-    
-    // Only show DE/WM if they're not "Unknown"
-    if info.desktop_environment != "Unknown" {
-        info_lines.push(format!("DE: {}", info.desktop_environment));
-    }
-    
-    if info.window_manager != "Unknown" && info.window_manager != info.desktop_environment {
-        info_lines.push(format!("WM: {}", info.window_manager));
-    }
-    
-    if info.terminal != "Unknown" {
-        info_lines.push(format!("Terminal: {}", info.terminal));
+    let mut selected: Vec<Box<dyn InfoModule>> = Vec::new();
+    for entry in &layout {
+        match modules::find(&entry.name) {
+            Some(module) => selected.push(module),
+            None => {
+                eprintln!("Unknown module '{}' (available: {})", entry.name, modules::names().join(", "));
+                process::exit(1);
+            }
+        }
     }
     
-    info_lines.extend(vec![
-        format!("CPU: {}", info.cpu),
-        format!("Memory: {}", info.memory),
-    ]);
-    
-    if info.package_count != "Unknown" {
-        info_lines.push(format!("Packages: {}", info.package_count));
+    // Get system information
+    let ctx = Context::new(config).with_root(&root);
+    
+    if !ctx.is_live() {
+        let skipped: Vec<&str> = selected
+            .iter()
+            .filter(|module| module.is_live_only())
+            .map(|module| module.name())
+            .collect();
+        if !skipped.is_empty() {
+            eprintln!(
+                "Inspecting {}: skipping modules that describe the running system: {}",
+                root.display(),
+                skipped.join(", ")
+            );
+        }
     }
+    let system_info = SystemInfo::collect(&ctx, &selected);
     
-    info_lines
-}
-
-fn get_all_info(info: &SystemInfo) -> Vec<String> {
-    vec![
-        format!("OS: {}", info.os_name),
-        format!("Host: {}", info.hostname),
-        format!("Kernel: {}", info.kernel_version),
-        format!("Uptime: {}", info.uptime),
-        format!("Packages: {}", info.package_count),
-        format!("Shell: {}", info.shell),
-        format!("DE: {}", info.desktop_environment),
-        format!("WM: {}", info.window_manager),
-        format!("Terminal: {}", info.terminal),
-        format!("CPU: {}", info.cpu),
-        format!("Memory: {}", info.memory),
-    ]
-}
-
-fn display_output(ascii_lines: &[String], info_lines: &[String], theme: &colors::ThemeColors, no_color: bool) {
-    // Calculate the maximum width of ASCII art for proper alignment
-    let max_ascii_width = ascii_lines
-        .iter()
-        .map(|line| strip_ansi_codes(line).chars().count())
-        .max()
-        .unwrap_or(0);
-    
-    let max_lines = ascii_lines.len().max(info_lines.len());
-    let padding_base = if max_ascii_width > 0 { 4 } else { 0 }; // Base padding between ASCII and info
-    
-    for i in 0..max_lines {
-        let ascii_part = if i < ascii_lines.len() {
-            if no_color {
-                strip_ansi_codes(&ascii_lines[i])
-            } else {
-                ascii_lines[i].clone()
-            }
-        } else {
-            String::new()
-        };
-        
-        let info_part = if i < info_lines.len() {
-            if no_color {
-                info_lines[i].clone()
-            } else {
-                format_info_line(&info_lines[i], theme)
-            }
-        } else {
-            String::new()
-        };
-        
-        // Calculate dynamic padding
-        let ascii_display_width = strip_ansi_codes(&ascii_part).chars().count();
-        let padding_needed = if max_ascii_width > ascii_display_width {
-            max_ascii_width - ascii_display_width + padding_base
-        } else {
-            padding_base
-        };
-        
-        let padding = " ".repeat(padding_needed);
-        
-        // Print the line
-        if !ascii_part.is_empty() || !info_part.is_empty() {
-            print!("{}", ascii_part);
-            if !info_part.is_empty() {
-                print!("{}{}", padding, info_part);
+    if cli.format == OutputFormat::Json {
+        match serde_json::to_string_pretty(&build_json(&distro, &system_info, &layout)) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error serializing system information: {}", e);
+                process::exit(1);
             }
-            println!();
         }
+        return;
     }
-}
-
-fn format_info_line(line: &str, theme: &colors::ThemeColors) -> String {
-    if let Some(colon_pos) = line.find(':') {
-        let (label, value) = line.split_at(colon_pos);
-        let value_part = &value[1..]; // Remove the colon
-        
-        // Handle special cases for better formatting
-        let formatted_value = if value_part.trim().is_empty() || value_part.trim() == "Unknown" {
-            " N/A".dimmed().to_string()
-        } else {
-            format!(" {}", value_part.color(theme.text))
-        };
-        
-        format!("{}{}{}", 
-            label.color(theme.primary).bold(),
-            ":".color(theme.secondary),
-            formatted_value
-        )
+    
+    // Get ASCII art and colors, borrowing a parent distribution's
+    let logo = logo_distribution_from(ctx.source(), &distro);
+    let ascii_lines = if !no_art {
+        get_ascii_art(&logo)
     } else {
-        line.color(theme.text).to_string()
-    }
-}
-
-fn strip_ansi_codes(input: &str) -> String {
-    let mut result = String::new();
-    let mut chars = input.chars().peekable();
-    
-    while let Some(ch) = chars.next() {
-        if ch == '\x1b' {
-            // Handle ANSI escape sequences
-            if chars.peek() == Some(&'[') {
-                chars.next(); // consume '['
-                // Skip until we find a letter (the command)
-                while let Some(c) = chars.next() {
-                    if c.is_ascii_alphabetic() || c == 'm' {
-                        break;
-                    }
-                }
-            }
-        } else {
-            result.push(ch);
-        }
-    }
+        vec![]
+    };
+    
+    let theme = get_theme_colors(&logo);
     
-    result
+    let info_lines = get_info_lines(&system_info, &layout, &ctx.config, show_missing);
+    
+    // Display the output
+    print!("{}", render_output(&ascii_lines, &info_lines, &theme, no_color));
 }
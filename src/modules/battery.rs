@@ -0,0 +1,171 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_battery;
+
+const POWER_SUPPLY: &str = "/sys/class/power_supply";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryStatus {
+    Charging,
+    Discharging,
+    Full,
+    /// Plugged in but held below full, e.g. by a charge limit
+    NotCharging,
+    Unknown,
+}
+
+/// One battery: the system's own, or a peripheral's (mouse, headset, ...).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Battery {
+    /// Kernel name, e.g. `BAT0`
+    pub name: String,
+    pub model: Option<String>,
+    /// Whether this powers a peripheral rather than the system
+    pub peripheral: bool,
+    pub percentage: Option<u32>,
+    pub status: BatteryStatus,
+    /// Time until empty when discharging, or until full when charging
+    pub time_remaining_secs: Option<u64>,
+    /// Full capacity as a percentage of the design capacity
+    pub health_percentage: Option<u32>,
+}
+
+/// Every battery found, system batteries first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Batteries(pub Vec<Battery>);
+
+impl Render for Batteries {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        match self.0.as_slice() {
+            [battery] if !battery.peripheral => vec![Line::new(format_battery(battery))],
+            batteries => batteries
+                .iter()
+                .map(|battery| {
+                    let key = match (&battery.model, battery.peripheral) {
+                        (Some(model), true) => model.clone(),
+                        _ => battery.name.clone(),
+                    };
+                    Line::keyed(key, format_battery(battery))
+                })
+                .collect(),
+        }
+    }
+}
+
+pub struct BatteryModule;
+
+impl InfoModule for BatteryModule {
+    fn name(&self) -> &'static str {
+        "battery"
+    }
+
+    fn label(&self) -> &'static str {
+        "Battery"
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        !battery_dirs(ctx).is_empty()
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        let mut batteries: Vec<Battery> = battery_dirs(ctx)
+            .iter()
+            .filter_map(|dir| read_battery(ctx, dir))
+            .collect();
+        batteries.sort_by_key(|battery| battery.peripheral);
+        field(Some(Batteries(batteries)).filter(|batteries| !batteries.0.is_empty()))
+    }
+}
+
+/// Power supplies of type Battery that are present. AC adapters and USB
+/// ports are power supplies too.
+fn battery_dirs(ctx: &Context) -> Vec<PathBuf> {
+    ctx.read_dir(POWER_SUPPLY)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|name| Path::new(POWER_SUPPLY).join(name))
+        .filter(|dir| read_value(ctx, dir, "type").as_deref() == Some("Battery"))
+        .filter(|dir| read_value(ctx, dir, "present").as_deref() != Some("0"))
+        .collect()
+}
+
+fn read_value(ctx: &Context, dir: &Path, file: &str) -> Option<String> {
+    let content = ctx.read_to_string(dir.join(file))?;
+    let content = content.trim();
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+fn read_number(ctx: &Context, dir: &Path, file: &str) -> Option<u64> {
+    read_value(ctx, dir, file)?.parse().ok()
+}
+
+fn read_battery(ctx: &Context, dir: &Path) -> Option<Battery> {
+    let name = dir.file_name()?.to_string_lossy().into_owned();
+    let status = match read_value(ctx, dir, "status").as_deref() {
+        Some("Charging") => BatteryStatus::Charging,
+        Some("Discharging") => BatteryStatus::Discharging,
+        Some("Full") => BatteryStatus::Full,
+        Some("Not charging") => BatteryStatus::NotCharging,
+        _ => BatteryStatus::Unknown,
+    };
+
+    // Batteries report either energy (µWh, power in µW) or charge (µAh,
+    // current in µA); the ratios come out the same
+    let (now, full, design, rate) = match read_number(ctx, dir, "energy_now") {
+        Some(now) => (
+            Some(now),
+            read_number(ctx, dir, "energy_full"),
+            read_number(ctx, dir, "energy_full_design"),
+            read_number(ctx, dir, "power_now"),
+        ),
+        None => (
+            read_number(ctx, dir, "charge_now"),
+            read_number(ctx, dir, "charge_full"),
+            read_number(ctx, dir, "charge_full_design"),
+            read_number(ctx, dir, "current_now"),
+        ),
+    };
+
+    let percentage = read_number(ctx, dir, "capacity")
+        .or_else(|| Some(now? * 100 / full.filter(|&full| full > 0)?))
+        .map(|percentage| percentage.min(100) as u32);
+
+    let time_remaining_secs = match status {
+        BatteryStatus::Discharging => read_number(ctx, dir, "time_to_empty_now")
+            .or_else(|| hours_to_secs(now?, rate?)),
+        BatteryStatus::Charging => read_number(ctx, dir, "time_to_full_now")
+            .or_else(|| hours_to_secs(full?.saturating_sub(now?), rate?)),
+        _ => None,
+    };
+
+    let health_percentage = match (full, design) {
+        (Some(full), Some(design)) if design > 0 => Some((full * 100 / design) as u32),
+        _ => None,
+    };
+
+    Some(Battery {
+        model: read_value(ctx, dir, "model_name"),
+        // Peripherals are scoped to the device they power
+        peripheral: read_value(ctx, dir, "scope").as_deref() == Some("Device"),
+        percentage,
+        status,
+        time_remaining_secs,
+        health_percentage,
+        name,
+    })
+}
+
+/// Converts an amount over a rate (µWh over µW, or µAh over µA) to seconds.
+fn hours_to_secs(amount: u64, rate: u64) -> Option<u64> {
+    (rate > 0).then(|| Duration::from_secs_f64(amount as f64 / rate as f64 * 3600.0).as_secs())
+}
@@ -0,0 +1,139 @@
+//! Resource limits of the cgroup rustch runs in, as set by container
+//! runtimes and systemd. Both the unified (v2) and the legacy (v1)
+//! hierarchy are supported.
+
+use std::path::{Path, PathBuf};
+
+use super::Context;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// v1 reports "no limit" as a page-aligned `i64::MAX`; anything this large
+/// is unlimited.
+const V1_UNLIMITED: u64 = 1 << 62;
+
+/// Memory limit and usage of the cgroup, in bytes.
+pub(super) struct MemoryLimit {
+    pub limit: u64,
+    /// Usage without the inactive page cache, which the kernel reclaims
+    /// before hitting the limit; `docker stats` shows the same figure
+    pub used: u64,
+}
+
+/// The directories of `controller` from our cgroup up to the root of the
+/// hierarchy. Limits apply from every level, so the lowest one wins.
+/// Inside a container the cgroup named in `/proc/self/cgroup` may lie
+/// outside the mounted hierarchy; then only the mount root, which is the
+/// container's cgroup, is left.
+fn controller_dirs(ctx: &Context, controller: &str) -> Vec<PathBuf> {
+    let Some(cgroups) = ctx.read_to_string("/proc/self/cgroup") else {
+        return Vec::new();
+    };
+    let unified = ctx.exists(Path::new(CGROUP_ROOT).join("cgroup.controllers"));
+
+    // Lines are "id:controllers:path"; v2 has the single line "0::path"
+    let found = cgroups.lines().find_map(|line| {
+        let mut fields = line.splitn(3, ':');
+        let (_, controllers, path) = (fields.next()?, fields.next()?, fields.next()?);
+        if unified {
+            return controllers.is_empty().then(|| (PathBuf::from(CGROUP_ROOT), path));
+        }
+
+        controllers.split(',').any(|name| name == controller).then_some(())?;
+        // v1 mounts each hierarchy under its controllers, e.g. cpu,cpuacct
+        let mount = ctx
+            .read_dir(CGROUP_ROOT)?
+            .into_iter()
+            .find(|mount| mount.split(',').any(|name| name == controller))?;
+        Some((Path::new(CGROUP_ROOT).join(mount), path))
+    });
+    let Some((mount, path)) = found else {
+        return Vec::new();
+    };
+
+    let mut dirs: Vec<PathBuf> = Path::new(path.trim())
+        .ancestors()
+        .map(|ancestor| mount.join(ancestor.strip_prefix("/").unwrap_or(ancestor)))
+        .filter(|dir| ctx.is_dir(dir))
+        .collect();
+    if dirs.is_empty() && ctx.is_dir(&mount) {
+        dirs.push(mount);
+    }
+    dirs
+}
+
+fn read_number(ctx: &Context, path: &Path) -> Option<u64> {
+    ctx.read_to_string(path)?.trim().parse().ok()
+}
+
+/// Reads a `key value` line of `memory.stat`.
+fn read_stat(ctx: &Context, path: &Path, key: &str) -> Option<u64> {
+    ctx.read_to_string(path)?.lines().find_map(|line| {
+        let (name, value) = line.split_once(' ')?;
+        (name == key).then(|| value.trim().parse().ok())?
+    })
+}
+
+pub(super) fn memory_limit(ctx: &Context) -> Option<MemoryLimit> {
+    let dirs = controller_dirs(ctx, "memory");
+    let own = dirs.first()?;
+
+    let (limit, usage, inactive) = if dirs.iter().any(|dir| ctx.exists(dir.join("memory.max"))) {
+        (
+            dirs.iter().filter_map(|dir| read_number(ctx, &dir.join("memory.max"))).min()?,
+            read_number(ctx, &own.join("memory.current"))?,
+            read_stat(ctx, &own.join("memory.stat"), "inactive_file"),
+        )
+    } else {
+        (
+            dirs.iter()
+                .filter_map(|dir| read_number(ctx, &dir.join("memory.limit_in_bytes")))
+                .filter(|&limit| limit < V1_UNLIMITED)
+                .min()?,
+            read_number(ctx, &own.join("memory.usage_in_bytes"))?,
+            read_stat(ctx, &own.join("memory.stat"), "total_inactive_file"),
+        )
+    };
+
+    Some(MemoryLimit {
+        limit,
+        used: usage.saturating_sub(inactive.unwrap_or(0)),
+    })
+}
+
+/// The number of CPUs the cgroup may use: its CPU quota over the period,
+/// or the size of its cpuset, whichever is lower. Quotas can be fractional.
+pub(super) fn cpu_limit(ctx: &Context) -> Option<f64> {
+    let quota = controller_dirs(ctx, "cpu")
+        .iter()
+        .filter_map(|dir| {
+            // v2 has "quota period" in cpu.max, with "max" for no quota; v1
+            // has the two in separate files, with -1 for no quota
+            let (quota, period) = match ctx.read_to_string(dir.join("cpu.max")) {
+                Some(max) => {
+                    let (quota, period) = max.trim().split_once(' ')?;
+                    (quota.parse::<f64>().ok()?, period.parse::<f64>().ok()?)
+                }
+                None => (
+                    ctx.read_to_string(dir.join("cpu.cfs_quota_us"))?.trim().parse::<f64>().ok()?,
+                    ctx.read_to_string(dir.join("cpu.cfs_period_us"))?.trim().parse::<f64>().ok()?,
+                ),
+            };
+            (quota > 0.0 && period > 0.0).then(|| quota / period)
+        })
+        .reduce(f64::min);
+
+    // The effective cpuset already accounts for the parents
+    let cpuset = controller_dirs(ctx, "cpuset").first().and_then(|dir| {
+        let list = ["cpuset.cpus.effective", "cpuset.effective_cpus"]
+            .iter()
+            .find_map(|file| ctx.read_to_string(dir.join(file)))?;
+        let cpus = super::cpu::parse_cpu_list(list.trim())?;
+        (!cpus.is_empty()).then_some(cpus.len() as f64)
+    });
+
+    match (quota, cpuset) {
+        (Some(quota), Some(cpuset)) => Some(quota.min(cpuset)),
+        (limit, None) | (None, limit) => limit,
+    }
+}
@@ -0,0 +1,352 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use sysinfo::{CpuExt, SystemExt};
+
+use super::cgroup;
+use super::{field, Context, Field, InfoModule, Line, Render, Subsystem};
+use crate::config::Config;
+use crate::format::format_cpu;
+
+const CPU_DIR: &str = "/sys/devices/system/cpu";
+
+/// CPU model and topology. `logical_cores` counts hardware threads.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cpu {
+    pub brand: String,
+    pub logical_cores: usize,
+    pub physical_cores: Option<usize>,
+    pub sockets: Option<usize>,
+    /// Cores of each kind on hybrid CPUs (Intel P/E cores, ARM big.LITTLE)
+    pub performance_cores: Option<usize>,
+    pub efficiency_cores: Option<usize>,
+    pub max_frequency_mhz: Option<u32>,
+    /// Package temperature
+    pub temperature_celsius: Option<f64>,
+    /// CPUs a cgroup quota or cpuset lets us use, when fewer than
+    /// `logical_cores`; quotas can be fractional
+    pub cpu_limit: Option<f64>,
+}
+
+impl Render for Cpu {
+    fn render(&self, config: &Config) -> Vec<Line> {
+        vec![Line::new(format_cpu(self, &config.cpu))]
+    }
+}
+
+/// `[cpu]` section of the config file. Each option turns one part of the
+/// CPU line on or off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CpuConfig {
+    /// Sockets, cores and threads
+    pub topology: bool,
+    /// Maximum clock speed
+    pub frequency: bool,
+    /// Performance and efficiency core counts on hybrid CPUs
+    pub hybrid: bool,
+    /// Package temperature
+    pub temperature: bool,
+}
+
+impl Default for CpuConfig {
+    fn default() -> Self {
+        CpuConfig {
+            topology: true,
+            frequency: true,
+            hybrid: true,
+            temperature: true,
+        }
+    }
+}
+
+pub struct CpuModule;
+
+impl InfoModule for CpuModule {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn label(&self) -> &'static str {
+        "CPU"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_cpu_info(ctx))
+    }
+}
+
+fn get_cpu_info(ctx: &Context) -> Option<Cpu> {
+    let config = &ctx.config.cpu;
+    let cpuinfo = ctx.read_to_string("/proc/cpuinfo").and_then(|cpuinfo| parse_cpuinfo(&cpuinfo));
+    let mut cpu = match cpuinfo {
+        Some(cpu) => cpu,
+        // ARM kernels usually name no model; sysinfo derives one from the
+        // implementer and part numbers
+        None => {
+            let sys = ctx.refreshed(Subsystem::Cpu)?;
+            let cpus = sys.cpus();
+            Cpu {
+                brand: cpus.first()?.brand().to_string(),
+                logical_cores: cpus.len(),
+                physical_cores: sys.physical_core_count(),
+                sockets: None,
+                performance_cores: None,
+                efficiency_cores: None,
+                max_frequency_mhz: None,
+                temperature_celsius: None,
+                cpu_limit: None,
+            }
+        }
+    };
+    
+    // Clean up CPU brand name
+    cpu.brand = cpu
+        .brand
+        .trim()
+        .replace("(R)", "")
+        .replace("(TM)", "")
+        .replace("  ", " ")
+        .trim()
+        .to_string();
+    
+    // sysfs knows the topology on every architecture, /proc/cpuinfo only
+    // on some
+    let cpus = online_cpus(ctx);
+    let topology = read_topology(ctx, &cpus);
+    if let Some(topology) = &topology {
+        cpu.logical_cores = cpus.len();
+        cpu.physical_cores = Some(topology.cores());
+        cpu.sockets = Some(topology.sockets());
+    }
+    cpu.cpu_limit = cgroup::cpu_limit(ctx).filter(|&limit| limit < cpu.logical_cores as f64);
+    
+    if config.hybrid {
+        if let Some((performance, efficiency)) = hybrid_split(ctx, &cpus, topology.as_ref()) {
+            cpu.performance_cores = Some(performance);
+            cpu.efficiency_cores = Some(efficiency);
+        }
+    }
+    if config.frequency {
+        cpu.max_frequency_mhz = max_frequency_mhz(ctx, &cpus);
+    }
+    if config.temperature {
+        cpu.temperature_celsius = read_temperature(ctx);
+    }
+    
+    Some(cpu)
+}
+
+/// Reads the model and core counts from `/proc/cpuinfo`, which has one
+/// "key : value" stanza per logical CPU.
+fn parse_cpuinfo(content: &str) -> Option<Cpu> {
+    let mut brand = None;
+    let mut logical_cores = 0;
+    let mut cores = HashSet::new();
+    let mut physical_id: Option<String> = None;
+    
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "processor" => logical_cores += 1,
+            // x86 calls it "model name", some MIPS and RISC-V kernels "cpu
+            // model"; "Model" on ARM is the board, not the CPU
+            "model name" | "cpu model" if brand.is_none() => brand = Some(value.to_string()),
+            "physical id" => physical_id = Some(value.to_string()),
+            "core id" => {
+                cores.insert((physical_id.clone(), value.to_string()));
+            }
+            _ => {}
+        }
+    }
+    
+    let sockets: HashSet<_> = cores.iter().filter_map(|(socket, _)| socket.as_ref()).collect();
+    Some(Cpu {
+        brand: brand?,
+        logical_cores,
+        physical_cores: (!cores.is_empty()).then_some(cores.len()),
+        sockets: (!sockets.is_empty()).then_some(sockets.len()),
+        performance_cores: None,
+        efficiency_cores: None,
+        max_frequency_mhz: None,
+        temperature_celsius: None,
+        cpu_limit: None,
+    })
+}
+
+fn cpu_dir(cpu: u32) -> PathBuf {
+    Path::new(CPU_DIR).join(format!("cpu{}", cpu))
+}
+
+fn read_trimmed(ctx: &Context, path: impl AsRef<Path>) -> Option<String> {
+    let content = ctx.read_to_string(path)?;
+    let content = content.trim();
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+/// The online CPUs listed in sysfs, in order. CPUs taken offline through
+/// `cpuN/online` are left out.
+fn online_cpus(ctx: &Context) -> Vec<u32> {
+    let mut cpus: Vec<u32> = ctx
+        .read_dir(CPU_DIR)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|name| name.strip_prefix("cpu")?.parse().ok())
+        .filter(|&cpu| read_trimmed(ctx, cpu_dir(cpu).join("online")).as_deref() != Some("0"))
+        .collect();
+    cpus.sort_unstable();
+    cpus
+}
+
+/// The socket and core of every online CPU.
+struct Topology(HashMap<u32, (String, String)>);
+
+impl Topology {
+    fn cores(&self) -> usize {
+        self.0.values().collect::<HashSet<_>>().len()
+    }
+
+    fn sockets(&self) -> usize {
+        self.0.values().map(|(socket, _)| socket).collect::<HashSet<_>>().len()
+    }
+
+    /// Distinct cores among `cpus`.
+    fn cores_of(&self, cpus: &[u32]) -> usize {
+        cpus.iter().filter_map(|cpu| self.0.get(cpu)).collect::<HashSet<_>>().len()
+    }
+}
+
+fn read_topology(ctx: &Context, cpus: &[u32]) -> Option<Topology> {
+    if cpus.is_empty() {
+        return None;
+    }
+    
+    let mut topology = HashMap::new();
+    for &cpu in cpus {
+        let dir = cpu_dir(cpu).join("topology");
+        let socket = read_trimmed(ctx, dir.join("physical_package_id"))?;
+        let core = read_trimmed(ctx, dir.join("core_id"))?;
+        topology.insert(cpu, (socket, core));
+    }
+    Some(Topology(topology))
+}
+
+/// Performance and efficiency core counts, if the CPU has both kinds.
+fn hybrid_split(ctx: &Context, cpus: &[u32], topology: Option<&Topology>) -> Option<(usize, usize)> {
+    let count = |cpus: &[u32]| match topology {
+        Some(topology) => topology.cores_of(cpus),
+        None => cpus.len(),
+    };
+    
+    // Intel hybrid CPUs register a perf PMU for each core type
+    let core = read_trimmed(ctx, "/sys/devices/cpu_core/cpus").and_then(|list| parse_cpu_list(&list));
+    let atom = read_trimmed(ctx, "/sys/devices/cpu_atom/cpus").and_then(|list| parse_cpu_list(&list));
+    if let (Some(core), Some(atom)) = (core, atom) {
+        return Some((count(&core), count(&atom)));
+    }
+    
+    // ARM rates each CPU's capacity relative to the fastest, which is 1024
+    let capacities: Vec<(u32, u32)> = cpus
+        .iter()
+        .filter_map(|&cpu| Some((cpu, read_trimmed(ctx, cpu_dir(cpu).join("cpu_capacity"))?.parse().ok()?)))
+        .collect();
+    let max = capacities.iter().map(|&(_, capacity)| capacity).max()?;
+    let (big, little): (Vec<_>, Vec<_>) = capacities.iter().partition(|&&(_, capacity)| capacity == max);
+    if little.is_empty() {
+        return None;
+    }
+    
+    let ids = |cpus: Vec<&(u32, u32)>| cpus.into_iter().map(|&(cpu, _)| cpu).collect::<Vec<_>>();
+    Some((count(&ids(big)), count(&ids(little))))
+}
+
+/// Parses a sysfs CPU list such as `0-7,16-23`.
+pub(super) fn parse_cpu_list(list: &str) -> Option<Vec<u32>> {
+    let mut cpus = Vec::new();
+    for range in list.split(',') {
+        match range.split_once('-') {
+            Some((start, end)) => cpus.extend(start.parse::<u32>().ok()?..=end.parse().ok()?),
+            None => cpus.push(range.parse().ok()?),
+        }
+    }
+    Some(cpus)
+}
+
+/// The highest `cpuinfo_max_freq` of any CPU; on hybrid CPUs the cores run
+/// at different speeds.
+fn max_frequency_mhz(ctx: &Context, cpus: &[u32]) -> Option<u32> {
+    cpus.iter()
+        .filter_map(|&cpu| read_trimmed(ctx, cpu_dir(cpu).join("cpufreq/cpuinfo_max_freq")))
+        .filter_map(|khz| khz.parse::<u32>().ok())
+        .max()
+        .map(|khz| khz / 1000)
+        .filter(|&mhz| mhz > 0)
+}
+
+/// Reads the package temperature from the CPU's hwmon driver, falling back
+/// to the thermal zone the platform labels as the CPU's.
+fn read_temperature(ctx: &Context) -> Option<f64> {
+    // hwmon drivers for CPU sensors, and the labels of their package sensor
+    // in order of preference. Without a matching label temp1 is used.
+    let drivers: &[(&str, &[&str])] = &[
+        ("coretemp", &["Package id 0"]),
+        ("k10temp", &["Tctl", "Tdie"]),
+        ("zenpower", &["Tctl", "Tdie"]),
+        ("cpu_thermal", &[]),
+    ];
+    
+    let hwmon = Path::new("/sys/class/hwmon");
+    for name in ctx.read_dir(hwmon).unwrap_or_default() {
+        let dir = hwmon.join(name);
+        let Some(driver) = read_trimmed(ctx, dir.join("name")) else {
+            continue;
+        };
+        let Some((_, labels)) = drivers.iter().find(|(name, _)| *name == driver) else {
+            continue;
+        };
+        
+        let sensors: Vec<String> = ctx
+            .read_dir(&dir)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|file| Some(file.strip_suffix("_label")?.to_string()))
+            .collect();
+        let sensor = labels
+            .iter()
+            .find_map(|label| {
+                sensors
+                    .iter()
+                    .find(|sensor| read_trimmed(ctx, dir.join(format!("{}_label", sensor))).as_deref() == Some(label))
+            })
+            .map_or("temp1", String::as_str);
+        
+        if let Some(temperature) = read_millidegrees(ctx, &dir.join(format!("{}_input", sensor))) {
+            return Some(temperature);
+        }
+    }
+    
+    let zones = Path::new("/sys/class/thermal");
+    for name in ctx.read_dir(zones).unwrap_or_default() {
+        let dir = zones.join(name);
+        let zone_type = read_trimmed(ctx, dir.join("type")).unwrap_or_default();
+        if ["x86_pkg_temp", "cpu-thermal", "cpu_thermal", "cpu0-thermal", "soc_thermal"].contains(&zone_type.as_str()) {
+            if let Some(temperature) = read_millidegrees(ctx, &dir.join("temp")) {
+                return Some(temperature);
+            }
+        }
+    }
+    
+    None
+}
+
+fn read_millidegrees(ctx: &Context, path: &Path) -> Option<f64> {
+    let millidegrees: i64 = read_trimmed(ctx, path)?.parse().ok()?;
+    Some(millidegrees as f64 / 1000.0)
+}
@@ -0,0 +1,87 @@
+use super::{field, Context, Field, InfoModule};
+
+pub struct DesktopEnvironment;
+
+impl InfoModule for DesktopEnvironment {
+    fn name(&self) -> &'static str {
+        "de"
+    }
+
+    fn label(&self) -> &'static str {
+        "DE"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        has_graphical_session(ctx)
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_desktop_environment(ctx))
+    }
+}
+
+/// Whether we are running inside a graphical session. On a bare TTY or over
+/// SSH there is no desktop or window manager to report.
+pub(super) fn has_graphical_session(ctx: &Context) -> bool {
+    if ctx.source().os() == "macos" {
+        return true;
+    }
+    
+    ["DISPLAY", "WAYLAND_DISPLAY", "XDG_CURRENT_DESKTOP"]
+        .iter()
+        .any(|var| ctx.var(var).is_some_and(|value| !value.is_empty()))
+}
+
+pub(super) fn get_desktop_environment(ctx: &Context) -> Option<String> {
+    // Priority order for DE detection
+    let de_vars = vec![
+        ("XDG_CURRENT_DESKTOP", true),
+        ("XDG_SESSION_DESKTOP", true),
+        ("DESKTOP_SESSION", true),
+        ("GNOME_DESKTOP_SESSION_ID", false),
+        ("KDE_FULL_SESSION", false),
+        ("MATE_DESKTOP_SESSION_ID", false),
+    ];
+    
+    for (var, should_return_value) in de_vars {
+        if let Some(value) = ctx.var(var) {
+            if !value.is_empty() {
+                if should_return_value {
+                    return Some(normalize_de_name(&value));
+                } else {
+                    // For boolean-like vars, return the DE name
+                    let name = match var {
+                        "GNOME_DESKTOP_SESSION_ID" => "GNOME",
+                        "KDE_FULL_SESSION" => "KDE Plasma",
+                        "MATE_DESKTOP_SESSION_ID" => "MATE",
+                        _ => continue,
+                    };
+                    return Some(name.to_string());
+                }
+            }
+        }
+    }
+    
+    None
+}
+
+fn normalize_de_name(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "ubuntu:gnome" | "gnome" => "GNOME".to_string(),
+        "kde" => "KDE Plasma".to_string(),
+        "xfce" => "Xfce".to_string(),
+        "lxde" => "LXDE".to_string(),
+        "lxqt" => "LXQt".to_string(),
+        "mate" => "MATE".to_string(),
+        "cinnamon" => "Cinnamon".to_string(),
+        "pantheon" => "Pantheon".to_string(),
+        "budgie:gnome" | "budgie" => "Budgie".to_string(),
+        "i3" => "i3".to_string(),
+        "sway" => "Sway".to_string(),
+        _ => name.to_string(),
+    }
+}
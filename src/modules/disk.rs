@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_disk;
+
+/// Filesystems that do not store files on a disk: kernel interfaces, RAM
+/// backed filesystems, container layers and read-only snap images.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs", "binfmt_misc", "bpf", "cgroup", "cgroup2", "configfs", "debugfs", "devpts", "devtmpfs",
+    "efivarfs", "fusectl", "fuse.gvfsd-fuse", "fuse.portal", "hugetlbfs", "mqueue", "nsfs", "overlay",
+    "proc", "pstore", "ramfs", "rootfs", "rpc_pipefs", "securityfs", "selinuxfs", "squashfs", "sysfs",
+    "tmpfs", "tracefs",
+];
+
+/// Usage of one mounted filesystem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Disk {
+    pub mount_point: String,
+    /// Mounted device, e.g. `/dev/nvme0n1p2`
+    pub device: Option<String>,
+    /// Filesystem type, e.g. `ext4`
+    pub filesystem: Option<String>,
+    pub used_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// The filesystems selected by the `[disk]` config, in mount order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Disks(pub Vec<Disk>);
+
+impl Render for Disks {
+    fn render(&self, config: &Config) -> Vec<Line> {
+        self.0
+            .iter()
+            .map(|disk| Line::keyed(disk.mount_point.clone(), format_disk(disk, config.disk.bar)))
+            .collect()
+    }
+}
+
+/// `[disk]` section of the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiskConfig {
+    /// Mount points to show; an empty list shows every filesystem except
+    /// pseudo ones (tmpfs, overlay, snap images, ...)
+    pub mounts: Vec<String>,
+    /// Add a usage bar colored by how full the filesystem is
+    pub bar: bool,
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        DiskConfig {
+            mounts: vec!["/".to_string()],
+            bar: false,
+        }
+    }
+}
+
+pub struct DiskModule;
+
+impl InfoModule for DiskModule {
+    fn name(&self) -> &'static str {
+        "disk"
+    }
+
+    fn label(&self) -> &'static str {
+        "Disk"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        let disks = get_disks(ctx);
+        field(Some(disks).filter(|disks| !disks.0.is_empty()))
+    }
+}
+
+/// One line of `/proc/mounts`.
+struct Mount {
+    device: String,
+    mount_point: String,
+    filesystem: String,
+}
+
+fn get_disks(ctx: &Context) -> Disks {
+    let mounts = ctx.read_to_string("/proc/mounts").map(|content| parse_mounts(&content)).unwrap_or_default();
+    let configured = &ctx.config.disk.mounts;
+
+    let selected: Vec<(String, Option<&Mount>)> = if configured.is_empty() {
+        let mut devices = HashSet::new();
+        mounts
+            .iter()
+            .filter(|mount| is_real_filesystem(mount))
+            // Bind mounts and btrfs subvolumes show the same device again
+            .filter(|mount| devices.insert(&mount.device))
+            .map(|mount| (mount.mount_point.clone(), Some(mount)))
+            .collect()
+    } else {
+        // Later mounts hide earlier ones on the same mount point
+        configured
+            .iter()
+            .map(|mount_point| {
+                let mount = mounts.iter().rev().find(|mount| &mount.mount_point == mount_point);
+                (mount_point.clone(), mount)
+            })
+            .collect()
+    };
+
+    let disks = selected
+        .into_iter()
+        .filter_map(|(mount_point, mount)| {
+            let usage = ctx.source().filesystem_usage(Path::new(&mount_point))?;
+            if usage.total_bytes == 0 {
+                return None;
+            }
+
+            Some(Disk {
+                device: mount.map(|mount| mount.device.clone()),
+                filesystem: mount.map(|mount| mount.filesystem.clone()),
+                used_bytes: usage.total_bytes.saturating_sub(usage.available_bytes),
+                total_bytes: usage.total_bytes,
+                mount_point,
+            })
+        })
+        .collect();
+
+    Disks(disks)
+}
+
+fn is_real_filesystem(mount: &Mount) -> bool {
+    // Kernel interfaces and runtime state, except removable media that
+    // udisks mounts under /run/media
+    let path = Path::new(&mount.mount_point);
+    let hidden_path = ["/proc", "/sys", "/dev"].iter().any(|prefix| path.starts_with(prefix))
+        || (path.starts_with("/run") && !path.starts_with("/run/media"));
+
+    !hidden_path && !PSEUDO_FILESYSTEMS.contains(&mount.filesystem.as_str())
+}
+
+/// Parses `/proc/mounts`: device, mount point, type and options separated
+/// by spaces, with spaces inside fields escaped as `\040`.
+fn parse_mounts(content: &str) -> Vec<Mount> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(Mount {
+                device: unescape_octal(fields.next()?),
+                mount_point: unescape_octal(fields.next()?),
+                filesystem: fields.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn unescape_octal(field: &str) -> String {
+    let mut bytes = Vec::with_capacity(field.len());
+    let raw = field.as_bytes();
+    let mut i = 0;
+    while i < raw.len() {
+        let octal = raw.get(i + 1..i + 4).and_then(|digits| {
+            let digits = std::str::from_utf8(digits).ok()?;
+            u8::from_str_radix(digits, 8).ok()
+        });
+        match (raw[i], octal) {
+            (b'\\', Some(byte)) => {
+                bytes.push(byte);
+                i += 4;
+            }
+            (byte, _) => {
+                bytes.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -0,0 +1,291 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_monitor;
+
+const DRM_DIR: &str = "/sys/class/drm";
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+
+/// Names for the PNP ids of common monitor and panel makers. Other makers
+/// are shown by their three-letter id.
+const MANUFACTURERS: &[(&str, &str)] = &[
+    ("ACR", "Acer"),
+    ("AOC", "AOC"),
+    ("APP", "Apple"),
+    ("AUO", "AU Optronics"),
+    ("AUS", "ASUS"),
+    ("BNQ", "BenQ"),
+    ("BOE", "BOE"),
+    ("CMN", "Innolux"),
+    ("DEL", "Dell"),
+    ("ENC", "EIZO"),
+    ("GBT", "Gigabyte"),
+    ("GSM", "LG"),
+    ("HPN", "HP"),
+    ("HWP", "HP"),
+    ("IVM", "iiyama"),
+    ("LEN", "Lenovo"),
+    ("LGD", "LG Display"),
+    ("MSI", "MSI"),
+    ("NEC", "NEC"),
+    ("PHL", "Philips"),
+    ("SAM", "Samsung"),
+    ("SDC", "Samsung"),
+    ("SHP", "Sharp"),
+    ("SNY", "Sony"),
+    ("VSC", "ViewSonic"),
+];
+
+/// A video mode. The refresh rate is in Hz.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Mode {
+    pub width: u32,
+    pub height: u32,
+    pub refresh_hz: Option<f64>,
+}
+
+/// A monitor plugged into a connector of the GPU.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Monitor {
+    /// DRM connector, e.g. `DP-1` or `eDP-1` for a laptop panel
+    pub connector: String,
+    pub manufacturer: Option<String>,
+    pub model: Option<String>,
+    /// Mode in use, known only when the DRM state is readable (as root)
+    pub current: Option<Mode>,
+    /// The monitor's native mode
+    pub preferred: Option<Mode>,
+    pub width_mm: Option<u32>,
+    pub height_mm: Option<u32>,
+}
+
+/// Every connected monitor, in connector order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Monitors(pub Vec<Monitor>);
+
+impl Render for Monitors {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        self.0
+            .iter()
+            .map(|monitor| Line::keyed(monitor.connector.clone(), format_monitor(monitor)))
+            .collect()
+    }
+}
+
+pub struct DisplayModule;
+
+impl InfoModule for DisplayModule {
+    fn name(&self) -> &'static str {
+        "display"
+    }
+
+    fn label(&self) -> &'static str {
+        "Display"
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        ctx.is_dir(DRM_DIR)
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        let monitors = get_monitors(ctx);
+        field(Some(monitors).filter(|monitors| !monitors.0.is_empty()))
+    }
+}
+
+fn get_monitors(ctx: &Context) -> Monitors {
+    let mut current_modes: HashMap<String, HashMap<String, Mode>> = HashMap::new();
+
+    let monitors = ctx
+        .read_dir(DRM_DIR)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| {
+            // Connectors are named card<N>-<connector>, e.g. card0-HDMI-A-1
+            let (card, connector) = entry.strip_prefix("card")?.split_once('-')?;
+            let dir = Path::new(DRM_DIR).join(&entry);
+            if ctx.read_to_string(dir.join("status"))?.trim() != "connected" {
+                return None;
+            }
+
+            let edid = ctx.source().read(&dir.join("edid")).and_then(|edid| Edid::parse(&edid));
+            // The first listed mode is the preferred one
+            let first_mode = ctx
+                .read_to_string(dir.join("modes"))
+                .and_then(|modes| parse_resolution(modes.lines().next()?));
+            let current = current_modes
+                .entry(card.to_string())
+                .or_insert_with(|| read_current_modes(ctx, card))
+                .get(connector)
+                .copied();
+
+            let edid = edid.unwrap_or_default();
+            Some(Monitor {
+                connector: connector.to_string(),
+                manufacturer: edid.manufacturer,
+                model: edid.model,
+                current,
+                preferred: edid.preferred.or(first_mode),
+                width_mm: edid.width_mm,
+                height_mm: edid.height_mm,
+            })
+        })
+        .collect();
+
+    Monitors(monitors)
+}
+
+/// Parses "2560x1440" and the "1920x1080i" of interlaced modes.
+fn parse_resolution(mode: &str) -> Option<Mode> {
+    let (width, height) = mode.trim().split_once('x')?;
+    let height = height.trim_end_matches(|c: char| !c.is_ascii_digit());
+    Some(Mode {
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+        refresh_hz: None,
+    })
+}
+
+/// Reads the mode of each active connector from the atomic state that DRM
+/// exposes in debugfs. Connectors name their CRTC, and the CRTC holds the
+/// mode:
+///
+/// ```text
+/// crtc[80]: crtc-0
+///     mode: "2560x1440": 60 241500 2560 2608 2640 2720 1440 1443 1448 1481 0x48 0x9
+/// connector[95]: DP-1
+///     crtc=crtc-0
+/// ```
+fn read_current_modes(ctx: &Context, card: &str) -> HashMap<String, Mode> {
+    let Some(state) = ctx.read_to_string(Path::new("/sys/kernel/debug/dri").join(card).join("state")) else {
+        return HashMap::new();
+    };
+
+    let mut crtc_modes = HashMap::new();
+    let mut connector_crtcs = Vec::new();
+    let mut section: Option<(&str, &str)> = None;
+    for line in state.lines() {
+        if let Some((kind, name)) = line.split_once("]: ") {
+            section = kind.split_once('[').map(|(kind, _)| (kind, name.trim()));
+            continue;
+        }
+
+        let line = line.trim();
+        match section {
+            Some(("crtc", crtc)) => {
+                if let Some(mode) = line.strip_prefix("mode: \"") {
+                    let Some((resolution, timings)) = mode.split_once("\": ") else {
+                        continue;
+                    };
+                    if let Some(mut mode) = parse_resolution(resolution) {
+                        mode.refresh_hz = timings.split_whitespace().next().and_then(|hz| hz.parse().ok());
+                        crtc_modes.insert(crtc, mode);
+                    }
+                }
+            }
+            Some(("connector", connector)) => {
+                if let Some(crtc) = line.strip_prefix("crtc=") {
+                    connector_crtcs.push((connector.to_string(), crtc));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    connector_crtcs
+        .into_iter()
+        .filter_map(|(connector, crtc)| Some((connector, *crtc_modes.get(crtc)?)))
+        .collect()
+}
+
+/// The parts of an EDID base block that describe the monitor.
+#[derive(Debug, Default)]
+struct Edid {
+    manufacturer: Option<String>,
+    model: Option<String>,
+    preferred: Option<Mode>,
+    width_mm: Option<u32>,
+    height_mm: Option<u32>,
+}
+
+impl Edid {
+    /// Parses the 128-byte base block; extension blocks are ignored.
+    fn parse(edid: &[u8]) -> Option<Self> {
+        if edid.len() < 128 || edid[..8] != EDID_HEADER {
+            return None;
+        }
+
+        // Three letters of five bits each, 1 for 'A'
+        let id = u16::from_be_bytes([edid[8], edid[9]]);
+        let pnp_id: String = [10, 5, 0]
+            .iter()
+            .map(|shift| (b'A' - 1 + ((id >> shift) & 0x1f) as u8) as char)
+            .collect();
+        let manufacturer = MANUFACTURERS
+            .iter()
+            .find(|(code, _)| *code == pnp_id)
+            .map_or(pnp_id, |(_, name)| name.to_string());
+
+        // Four 18-byte descriptors: detailed timings, the first being the
+        // preferred mode, or display descriptors such as the model name
+        let mut edid_info = Edid {
+            manufacturer: Some(manufacturer),
+            ..Edid::default()
+        };
+        for descriptor in edid[54..126].chunks(18) {
+            if descriptor[..2] != [0, 0] {
+                if edid_info.preferred.is_none() {
+                    edid_info.read_timing(descriptor);
+                }
+            } else if descriptor[3] == 0xfc {
+                let name = String::from_utf8_lossy(&descriptor[5..]);
+                let name = name.split('\n').next().unwrap_or_default().trim();
+                edid_info.model = (!name.is_empty()).then(|| name.to_string());
+            }
+        }
+
+        // The image size in cm, for monitors without a detailed timing
+        if edid_info.width_mm.is_none() && edid[21] > 0 && edid[22] > 0 {
+            edid_info.width_mm = Some(edid[21] as u32 * 10);
+            edid_info.height_mm = Some(edid[22] as u32 * 10);
+        }
+
+        Some(edid_info)
+    }
+
+    /// Reads a detailed timing descriptor: active and blanking pixels are
+    /// 12-bit values whose high bits share a byte, the clock is in 10 kHz.
+    fn read_timing(&mut self, descriptor: &[u8]) {
+        let twelve_bits = |low: usize, high: usize, shift: u32| {
+            descriptor[low] as u32 | ((descriptor[high] as u32 >> shift) & 0x0f) << 8
+        };
+        let clock_hz = u16::from_le_bytes([descriptor[0], descriptor[1]]) as f64 * 10_000.0;
+        let width = twelve_bits(2, 4, 4);
+        let height = twelve_bits(5, 7, 4);
+        let total = (width + twelve_bits(3, 4, 0)) * (height + twelve_bits(6, 7, 0));
+
+        if width > 0 && height > 0 {
+            self.preferred = Some(Mode {
+                width,
+                height,
+                refresh_hz: (total > 0).then(|| clock_hz / total as f64),
+            });
+        }
+
+        let width_mm = twelve_bits(12, 14, 4);
+        let height_mm = twelve_bits(13, 14, 0);
+        if width_mm > 0 && height_mm > 0 {
+            self.width_mm = Some(width_mm);
+            self.height_mm = Some(height_mm);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use serde::Serialize;
+use std::path::Path;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::{format_bios, format_product};
+
+const DMI_DIR: &str = "/sys/class/dmi/id";
+
+/// Values firmware vendors leave in DMI fields they did not fill in,
+/// compared case-insensitively.
+const PLACEHOLDERS: &[&str] = &[
+    "to be filled by o.e.m.",
+    "to be filled by oem",
+    "o.e.m.",
+    "oem",
+    "default string",
+    "system product name",
+    "system manufacturer",
+    "system version",
+    "base board product name",
+    "base board manufacturer",
+    "not applicable",
+    "not specified",
+    "not available",
+    "none",
+    "n/a",
+    "invalid",
+    "undefined",
+    "unknown",
+    "type1productconfigid",
+    "0123456789",
+    "x.x",
+    "rev x.0x",
+];
+
+/// A machine or motherboard as described by its firmware.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Product {
+    pub vendor: Option<String>,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl Render for Product {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        vec![Line::new(format_product(self))]
+    }
+}
+
+/// The firmware itself. `date` is as reported, usually MM/DD/YYYY.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Bios {
+    pub vendor: Option<String>,
+    pub version: String,
+    pub date: Option<String>,
+}
+
+impl Render for Bios {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        vec![Line::new(format_bios(self))]
+    }
+}
+
+pub struct Model;
+
+impl InfoModule for Model {
+    fn name(&self) -> &'static str {
+        "model"
+    }
+
+    fn label(&self) -> &'static str {
+        "Model"
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        ctx.is_dir(DMI_DIR) || ctx.exists("/proc/device-tree/model")
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_model(ctx))
+    }
+}
+
+pub struct Board;
+
+impl InfoModule for Board {
+    fn name(&self) -> &'static str {
+        "board"
+    }
+
+    fn label(&self) -> &'static str {
+        "Board"
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        ctx.is_dir(DMI_DIR)
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(Some(Product {
+            vendor: read_dmi(ctx, "board_vendor"),
+            name: read_dmi(ctx, "board_name")?,
+            version: read_dmi(ctx, "board_version"),
+        }))
+    }
+}
+
+pub struct BiosModule;
+
+impl InfoModule for BiosModule {
+    fn name(&self) -> &'static str {
+        "bios"
+    }
+
+    fn label(&self) -> &'static str {
+        "BIOS"
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        ctx.is_dir(DMI_DIR)
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(Some(Bios {
+            vendor: read_dmi(ctx, "bios_vendor"),
+            version: read_dmi(ctx, "bios_version")?,
+            date: read_dmi(ctx, "bios_date"),
+        }))
+    }
+}
+
+fn get_model(ctx: &Context) -> Option<Product> {
+    if let Some(name) = read_dmi(ctx, "product_name") {
+        let vendor = read_dmi(ctx, "sys_vendor");
+        let version = read_dmi(ctx, "product_version");
+
+        // Lenovo puts the machine type in the product name and the model
+        // ("ThinkPad X1 Carbon Gen 9") in the version
+        if let (Some("LENOVO"), Some(model)) = (vendor.as_deref(), &version) {
+            return Some(Product {
+                vendor,
+                name: model.clone(),
+                version: Some(name),
+            });
+        }
+
+        return Some(Product { vendor, name, version });
+    }
+
+    // ARM boards describe themselves in the device tree instead
+    let model = ctx.read_to_string("/proc/device-tree/model")?;
+    let model = model.trim_end_matches('\0').trim();
+    (!model.is_empty()).then(|| Product {
+        vendor: None,
+        name: model.to_string(),
+        version: None,
+    })
+}
+
+fn read_dmi(ctx: &Context, file: &str) -> Option<String> {
+    let content = ctx.read_to_string(Path::new(DMI_DIR).join(file))?;
+    let value = content.trim();
+    let placeholder = value.is_empty() || PLACEHOLDERS.iter().any(|placeholder| value.eq_ignore_ascii_case(placeholder));
+    (!placeholder).then(|| value.to_string())
+}
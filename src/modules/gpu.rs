@@ -0,0 +1,189 @@
+use serde::Serialize;
+use std::path::Path;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_gpu;
+
+const PCI_DEVICES: &str = "/sys/bus/pci/devices";
+
+/// pci.ids locations used by the common distributions, in lookup order.
+const PCI_IDS_PATHS: &[&str] = &[
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+    "/var/lib/pciutils/pci.ids",
+];
+
+/// Short names for graphics vendors, used instead of the long pci.ids names
+/// ("Advanced Micro Devices, Inc. [AMD/ATI]") and when no pci.ids is
+/// installed.
+const VENDORS: &[(&str, &str)] = &[
+    ("1002", "AMD"),
+    ("1022", "AMD"),
+    ("10de", "NVIDIA"),
+    ("8086", "Intel"),
+    ("102b", "Matrox"),
+    ("1a03", "ASPEED"),
+    ("15ad", "VMware"),
+    ("1af4", "Red Hat"),
+    ("1b36", "Red Hat"),
+    ("1234", "QEMU"),
+    ("80ee", "VirtualBox"),
+    ("1414", "Microsoft"),
+    ("5143", "Qualcomm"),
+    ("13b5", "ARM"),
+];
+
+/// A display controller on the PCI bus.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Gpu {
+    /// Bus address, e.g. `0000:03:00.0`
+    pub pci_address: String,
+    /// PCI vendor and device ids as four hex digits
+    pub vendor_id: String,
+    pub device_id: String,
+    pub vendor: String,
+    /// Marketing name from pci.ids, if it knows the device
+    pub name: Option<String>,
+    /// Kernel driver bound to the device
+    pub driver: Option<String>,
+    pub vram_bytes: Option<u64>,
+}
+
+/// Every display controller found, in bus order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Gpus(pub Vec<Gpu>);
+
+impl Render for Gpus {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        match self.0.as_slice() {
+            [gpu] => vec![Line::new(format_gpu(gpu))],
+            gpus => gpus
+                .iter()
+                .enumerate()
+                .map(|(i, gpu)| Line::keyed((i + 1).to_string(), format_gpu(gpu)))
+                .collect(),
+        }
+    }
+}
+
+pub struct GpuModule;
+
+impl InfoModule for GpuModule {
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn label(&self) -> &'static str {
+        "GPU"
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        ctx.is_dir(PCI_DEVICES)
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        let gpus = get_gpus(ctx);
+        field(Some(gpus).filter(|gpus| !gpus.0.is_empty()))
+    }
+}
+
+fn get_gpus(ctx: &Context) -> Gpus {
+    let devices = ctx.read_dir(PCI_DEVICES).unwrap_or_default();
+    let pci_ids = PCI_IDS_PATHS.iter().find_map(|path| ctx.read_to_string(path));
+
+    let gpus = devices
+        .into_iter()
+        .filter_map(|address| {
+            let dir = Path::new(PCI_DEVICES).join(&address);
+
+            // Class 0x03 is "display controller": VGA, XGA, 3D and others
+            let class = read_hex(ctx, &dir.join("class"))?;
+            if !class.starts_with("03") {
+                return None;
+            }
+
+            let vendor_id = read_hex(ctx, &dir.join("vendor"))?;
+            let device_id = read_hex(ctx, &dir.join("device"))?;
+            let (vendor_name, name) = match &pci_ids {
+                Some(pci_ids) => lookup_pci_ids(pci_ids, &vendor_id, &device_id),
+                None => (None, None),
+            };
+            let vendor = short_vendor(&vendor_id)
+                .map(str::to_string)
+                .or(vendor_name)
+                .unwrap_or_else(|| format!("Vendor {}", vendor_id));
+
+            Some(Gpu {
+                driver: ctx
+                    .source()
+                    .read_link(&dir.join("driver"))
+                    .and_then(|link| link.file_name().map(|name| name.to_string_lossy().into_owned())),
+                // Only amdgpu reports the VRAM size in sysfs
+                vram_bytes: ctx
+                    .read_to_string(dir.join("mem_info_vram_total"))
+                    .and_then(|content| content.trim().parse().ok())
+                    .filter(|&bytes| bytes > 0),
+                pci_address: address,
+                vendor_id,
+                device_id,
+                vendor,
+                name,
+            })
+        })
+        .collect();
+
+    Gpus(gpus)
+}
+
+/// Reads a sysfs id such as `0x1002` as lowercase hex digits.
+fn read_hex(ctx: &Context, path: &Path) -> Option<String> {
+    let content = ctx.read_to_string(path)?;
+    let value = content.trim().trim_start_matches("0x").to_lowercase();
+    (!value.is_empty()).then_some(value)
+}
+
+fn short_vendor(vendor_id: &str) -> Option<&'static str> {
+    VENDORS
+        .iter()
+        .find(|(id, _)| *id == vendor_id)
+        .map(|(_, name)| *name)
+}
+
+/// Looks up the vendor and device names in a pci.ids database. Vendor lines
+/// start at column 0 (`1002  Advanced Micro Devices, Inc. [AMD/ATI]`) and are
+/// followed by their devices, indented by one tab (`73bf  Navi 21 [...]`).
+fn lookup_pci_ids(pci_ids: &str, vendor_id: &str, device_id: &str) -> (Option<String>, Option<String>) {
+    let mut lines = pci_ids.lines().skip_while(|line| !has_id(line, vendor_id));
+    let Some(vendor_line) = lines.next() else {
+        return (None, None);
+    };
+    let vendor = vendor_line[vendor_id.len()..].trim().to_string();
+
+    let device = lines
+        .take_while(|line| line.starts_with('\t') || line.starts_with('#') || line.is_empty())
+        .filter_map(|line| line.strip_prefix('\t'))
+        .find(|line| has_id(line, device_id))
+        .map(|line| marketing_name(line[device_id.len()..].trim()));
+
+    (Some(vendor), device)
+}
+
+fn has_id(line: &str, id: &str) -> bool {
+    line.get(..id.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(id)) && line[id.len()..].starts_with("  ")
+}
+
+/// pci.ids puts the marketing name of a chip in brackets after its code
+/// name: "GA104 [GeForce RTX 3070]" is shown as "GeForce RTX 3070".
+fn marketing_name(name: &str) -> String {
+    match (name.rfind('['), name.ends_with(']')) {
+        (Some(start), true) if start > 0 => name[start + 1..name.len() - 1].to_string(),
+        _ => name.to_string(),
+    }
+}
@@ -0,0 +1,83 @@
+use serde::Serialize;
+use sysinfo::SystemExt;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_title;
+
+/// The `user@hostname` heading shown above the other lines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Title {
+    /// Only known on the running system
+    pub user: Option<String>,
+    pub hostname: String,
+}
+
+impl Render for Title {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        let title = format_title(self);
+        let underline = "-".repeat(title.chars().count());
+        vec![Line::new(title), Line::new(underline)]
+    }
+}
+
+pub struct Host;
+
+impl InfoModule for Host {
+    fn name(&self) -> &'static str {
+        "host"
+    }
+
+    fn label(&self) -> &'static str {
+        "Host"
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_hostname(ctx))
+    }
+}
+
+pub struct TitleModule;
+
+impl InfoModule for TitleModule {
+    fn name(&self) -> &'static str {
+        "title"
+    }
+
+    /// Shown without a label
+    fn label(&self) -> &'static str {
+        ""
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        let user = ctx
+            .is_live()
+            .then(|| ctx.var("USER").or_else(|| ctx.var("LOGNAME")))
+            .flatten()
+            .filter(|user| !user.is_empty());
+        field(get_hostname(ctx).map(|hostname| Title { user, hostname }))
+    }
+}
+
+fn get_hostname(ctx: &Context) -> Option<String> {
+    if ctx.is_live() {
+        // The hostname the kernel currently uses
+        if let Some(hostname) = ctx.read_to_string("/proc/sys/kernel/hostname").as_deref().and_then(first_line) {
+            return Some(hostname);
+        }
+        if let Some(hostname) = ctx.system().and_then(|sys| sys.host_name()) {
+            return Some(hostname);
+        }
+    }
+    
+    // The configured hostname of an installed system
+    ctx.read_to_string("/etc/hostname").as_deref().and_then(first_line)
+}
+
+fn first_line(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+}
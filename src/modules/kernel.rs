@@ -0,0 +1,34 @@
+use sysinfo::SystemExt;
+
+use super::{field, Context, Field, InfoModule};
+
+pub struct Kernel;
+
+impl InfoModule for Kernel {
+    fn name(&self) -> &'static str {
+        "kernel"
+    }
+
+    fn label(&self) -> &'static str {
+        "Kernel"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_kernel_version(ctx))
+    }
+}
+
+fn get_kernel_version(ctx: &Context) -> Option<String> {
+    if let Some(release) = ctx.read_to_string("/proc/sys/kernel/osrelease") {
+        let release = release.trim();
+        if !release.is_empty() {
+            return Some(release.to_string());
+        }
+    }
+    
+    ctx.system()?.kernel_version()
+}
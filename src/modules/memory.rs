@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use sysinfo::SystemExt;
+
+use super::cgroup;
+use super::{field, Context, Field, InfoModule, Line, Render, Subsystem};
+use crate::config::Config;
+use crate::format::format_memory;
+
+/// Physical memory usage in bytes. Under a cgroup memory limit below the
+/// host's RAM, `used_bytes` and `total_bytes` are the cgroup's.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Memory {
+    pub used_bytes: u64,
+    pub total_bytes: u64,
+    /// The host's RAM when `total_bytes` is a cgroup limit
+    pub host_total_bytes: Option<u64>,
+}
+
+impl Render for Memory {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        vec![Line::new(format_memory(self))]
+    }
+}
+
+/// `[memory]` section of the config file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MemoryConfig {
+    pub used: UsedMemory,
+}
+
+/// How "used" memory is computed. Tools disagree mostly about page cache and
+/// shared memory, so the same system can show quite different figures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsedMemory {
+    /// `MemTotal - MemAvailable`, like `free` and most desktop monitors
+    #[default]
+    Available,
+    /// `MemTotal - MemFree - Buffers - Cached - SReclaimable + Shmem`, like htop
+    Htop,
+    /// sysinfo's figure, which applies cgroup memory limits itself
+    Sysinfo,
+}
+
+pub struct MemoryModule;
+
+impl InfoModule for MemoryModule {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn label(&self) -> &'static str {
+        "Memory"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_memory_info(ctx))
+    }
+}
+
+fn get_memory_info(ctx: &Context) -> Option<Memory> {
+    let formula = ctx.config.memory.used;
+    if formula != UsedMemory::Sysinfo {
+        if let Some(meminfo) = Meminfo::load(ctx) {
+            return meminfo.memory(formula).map(|memory| apply_cgroup_limit(ctx, memory));
+        }
+    }
+
+    let sys = ctx.refreshed(Subsystem::Memory)?;
+    let total_bytes = sys.total_memory();
+    if total_bytes == 0 {
+        return None;
+    }
+
+    Some(Memory {
+        used_bytes: sys.used_memory(),
+        total_bytes,
+        host_total_bytes: None,
+    })
+}
+
+/// Measures usage against the cgroup's memory limit when it is below the
+/// host's RAM, as in a container started with `--memory`.
+fn apply_cgroup_limit(ctx: &Context, memory: Memory) -> Memory {
+    match cgroup::memory_limit(ctx) {
+        Some(limit) if limit.limit < memory.total_bytes => Memory {
+            used_bytes: limit.used.min(limit.limit),
+            total_bytes: limit.limit,
+            host_total_bytes: Some(memory.total_bytes),
+        },
+        _ => memory,
+    }
+}
+
+/// The fields of `/proc/meminfo` we use, in bytes.
+#[derive(Debug, Default)]
+pub(super) struct Meminfo {
+    pub total: u64,
+    pub free: u64,
+    pub available: Option<u64>,
+    pub buffers: u64,
+    pub cached: u64,
+    pub shmem: u64,
+    pub sreclaimable: u64,
+    pub swap_total: u64,
+    pub swap_free: u64,
+}
+
+impl Meminfo {
+    pub fn load(ctx: &Context) -> Option<Self> {
+        Meminfo::parse(&ctx.read_to_string("/proc/meminfo")?)
+    }
+
+    /// Parses "Key:   1234 kB" lines.
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut meminfo = Meminfo::default();
+        let mut has_total = false;
+
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let Some(bytes) = value
+                .split_whitespace()
+                .next()
+                .and_then(|kib| kib.parse::<u64>().ok())
+                .map(|kib| kib * 1024)
+            else {
+                continue;
+            };
+
+            match key {
+                "MemTotal" => {
+                    meminfo.total = bytes;
+                    has_total = true;
+                }
+                "MemFree" => meminfo.free = bytes,
+                // Added in Linux 3.14
+                "MemAvailable" => meminfo.available = Some(bytes),
+                "Buffers" => meminfo.buffers = bytes,
+                "Cached" => meminfo.cached = bytes,
+                "Shmem" => meminfo.shmem = bytes,
+                "SReclaimable" => meminfo.sreclaimable = bytes,
+                "SwapTotal" => meminfo.swap_total = bytes,
+                "SwapFree" => meminfo.swap_free = bytes,
+                _ => {}
+            }
+        }
+
+        has_total.then_some(meminfo)
+    }
+
+    fn memory(&self, formula: UsedMemory) -> Option<Memory> {
+        if self.total == 0 {
+            return None;
+        }
+
+        // Shared memory (tmpfs, GPU buffers) is counted in Cached but
+        // cannot be dropped like the rest of the cache
+        let cache = (self.cached + self.sreclaimable).saturating_sub(self.shmem);
+        let used = match (formula, self.available) {
+            (UsedMemory::Available, Some(available)) => self.total.saturating_sub(available),
+            _ => self.total.saturating_sub(self.free + self.buffers + cache),
+        };
+
+        Some(Memory {
+            used_bytes: used,
+            total_bytes: self.total,
+            host_total_bytes: None,
+        })
+    }
+}
@@ -0,0 +1,336 @@
+//! Information modules. Each module collects one piece of information (the
+//! OS name, memory usage, ...) into a typed value that can be rendered as
+//! text or serialized as JSON. The registry below lists every built-in
+//! module; layouts and config files refer to modules by [`InfoModule::name`].
+
+use serde::Serialize;
+use serde_json::Value;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, PoisonError};
+use sysinfo::{CpuRefreshKind, RefreshKind, System, SystemExt};
+
+use crate::config::Config;
+use crate::source::{RealSource, SystemSource};
+
+mod battery;
+mod cgroup;
+mod cpu;
+mod de;
+mod disk;
+mod dmi;
+mod display;
+mod gpu;
+mod host;
+mod kernel;
+mod memory;
+mod network;
+mod os;
+mod packages;
+mod process;
+mod shell;
+mod swap;
+mod terminal;
+mod terminal_font;
+mod theme;
+mod uptime;
+mod virtualization;
+mod wm;
+
+pub use battery::{Batteries, Battery, BatteryStatus};
+pub use cpu::{Cpu, CpuConfig};
+pub use disk::{Disk, DiskConfig, Disks};
+pub use display::{Mode, Monitor, Monitors};
+pub use dmi::{Bios, Product};
+pub use gpu::{Gpu, Gpus};
+pub use host::Title;
+pub use memory::{Memory, MemoryConfig, UsedMemory};
+pub use network::{InterfaceKind, Network, NetworkConfig, NetworkInterface};
+pub use packages::{PackageCount, Packages, PackagesConfig, PackagesDisplay};
+pub use shell::Shell;
+pub use swap::{Swap, SwapDevice, SwapKind};
+pub use theme::{Font, Toolkits};
+pub use uptime::Uptime;
+pub use virtualization::Virtualization;
+pub use wm::WindowManager;
+
+/// A collector for one piece of system information.
+pub trait InfoModule: Send + Sync {
+    /// Stable identifier, used in config files and as the JSON key.
+    fn name(&self) -> &'static str;
+
+    /// Label shown before the value unless the config overrides it.
+    fn label(&self) -> &'static str;
+
+    /// Whether the module applies to this system at all. Unavailable modules
+    /// are skipped instead of being reported as unknown.
+    fn is_available(&self, _ctx: &Context) -> bool {
+        true
+    }
+
+    /// Whether the module describes the running system (uptime, memory, ...)
+    /// rather than what is installed. Live-only modules are skipped when
+    /// inspecting another root with `--root`.
+    fn is_live_only(&self) -> bool {
+        false
+    }
+
+    /// Collects the module's value, or `None` if it could not be detected.
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>>;
+}
+
+/// Text rendering of a module value.
+pub trait Render {
+    /// The lines to display; most values render to exactly one line.
+    fn render(&self, config: &Config) -> Vec<Line>;
+}
+
+/// A collected value. Implemented for every `Render + Serialize` type, so
+/// module values only need those two.
+pub trait Field: Render + fmt::Debug + Send + Sync {
+    fn to_json(&self) -> Value;
+
+    /// Gives access to the concrete type, see [`SystemInfo::get_as`](crate::SystemInfo::get_as).
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Render + Serialize + fmt::Debug + Send + Sync + 'static> Field for T {
+    fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// One rendered line. `key` distinguishes lines of the same module, and is
+/// shown as `Label (key): value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub key: Option<String>,
+    pub value: String,
+}
+
+impl Line {
+    pub fn new(value: impl Into<String>) -> Self {
+        Line {
+            key: None,
+            value: value.into(),
+        }
+    }
+
+    pub fn keyed(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Line {
+            key: Some(key.into()),
+            value: value.into(),
+        }
+    }
+}
+
+impl Render for String {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        vec![Line::new(self.clone())]
+    }
+}
+
+/// A sysinfo subsystem that must be refreshed before it can be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Cpu,
+    Memory,
+}
+
+/// State shared by all modules during one collection run. Modules run
+/// concurrently, so everything here is loaded lazily and only once.
+///
+/// Modules read files, environment variables and command output through the
+/// context rather than directly, so that they see the system described by
+/// its [`SystemSource`].
+pub struct Context {
+    pub config: Config,
+    source: Box<dyn SystemSource>,
+    base: System,
+    cpu: OnceLock<System>,
+    memory: OnceLock<System>,
+    /// Keys and values of each gsettings schema read so far
+    gsettings: Mutex<HashMap<String, Option<HashMap<String, String>>>>,
+}
+
+impl Context {
+    /// A context for the running system.
+    pub fn new(config: Config) -> Self {
+        Context {
+            config,
+            source: Box::new(RealSource::new()),
+            base: System::new(),
+            cpu: OnceLock::new(),
+            memory: OnceLock::new(),
+            gsettings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Inspects the system installed under `root` instead of the running one.
+    pub fn with_root(self, root: impl Into<PathBuf>) -> Self {
+        self.with_source(RealSource::with_root(root))
+    }
+
+    /// Reads everything from `source`, such as a test fixture.
+    pub fn with_source(mut self, source: impl SystemSource + 'static) -> Self {
+        self.source = Box::new(source);
+        self
+    }
+
+    pub fn source(&self) -> &dyn SystemSource {
+        self.source.as_ref()
+    }
+
+    pub fn root(&self) -> &Path {
+        self.source.root()
+    }
+
+    /// Whether we are looking at a running system rather than a `--root`.
+    pub fn is_live(&self) -> bool {
+        self.source.is_live()
+    }
+
+    /// Reads a file such as `/var/lib/dpkg/status` of the inspected system.
+    pub fn read_to_string(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.source.read_to_string(path.as_ref())
+    }
+
+    /// Names of the entries of a directory of the inspected system, sorted.
+    pub fn read_dir(&self, path: impl AsRef<Path>) -> Option<Vec<String>> {
+        self.source.read_dir(path.as_ref())
+    }
+
+    pub fn exists(&self, path: impl AsRef<Path>) -> bool {
+        self.source.exists(path.as_ref())
+    }
+
+    pub fn is_dir(&self, path: impl AsRef<Path>) -> bool {
+        self.source.is_dir(path.as_ref())
+    }
+
+    pub fn var(&self, name: &str) -> Option<String> {
+        self.source.var(name)
+    }
+
+    /// Runs a command and returns its standard output if it succeeded.
+    pub fn run(&self, program: &str, args: &[&str]) -> Option<String> {
+        self.source
+            .run(program, args)
+            .filter(|output| output.success)
+            .map(|output| output.stdout)
+    }
+
+    /// A key of a gsettings schema, as printed by `gsettings get` ("'Adwaita'").
+    /// The whole schema is listed on first use, so that the modules reading
+    /// it share a single `gsettings` process.
+    pub fn gsettings(&self, schema: &str, key: &str) -> Option<String> {
+        let mut schemas = self.gsettings.lock().unwrap_or_else(PoisonError::into_inner);
+        let values = schemas.entry(schema.to_string()).or_insert_with(|| {
+            // Lines are "schema key value"; child schemas are listed too
+            let output = self.run("gsettings", &["list-recursively", schema])?;
+            let values = output
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.splitn(3, ' ');
+                    (fields.next()? == schema).then_some(())?;
+                    Some((fields.next()?.to_string(), fields.next()?.to_string()))
+                })
+                .collect();
+            Some(values)
+        });
+        values.as_ref()?.get(key).cloned()
+    }
+
+    /// A sysinfo handle with nothing refreshed, for values read on demand.
+    /// Only available when inspecting the machine rustch runs on.
+    pub fn system(&self) -> Option<&System> {
+        self.source.is_host().then_some(&self.base)
+    }
+
+    /// A sysinfo handle with `subsystem` refreshed. The refresh happens on
+    /// first use and is shared by later callers. Only available when
+    /// inspecting the machine rustch runs on.
+    pub fn refreshed(&self, subsystem: Subsystem) -> Option<&System> {
+        if !self.source.is_host() {
+            return None;
+        }
+        Some(match subsystem {
+            Subsystem::Cpu => self.cpu.get_or_init(|| {
+                System::new_with_specifics(RefreshKind::new().with_cpu(CpuRefreshKind::new()))
+            }),
+            Subsystem::Memory => self.memory.get_or_init(|| {
+                System::new_with_specifics(RefreshKind::new().with_memory())
+            }),
+        })
+    }
+}
+
+/// Every built-in module, in the order used by `--all`.
+pub fn registry() -> Vec<Box<dyn InfoModule>> {
+    vec![
+        Box::new(host::TitleModule),
+        Box::new(os::Os),
+        Box::new(host::Host),
+        Box::new(dmi::Model),
+        Box::new(dmi::Board),
+        Box::new(dmi::BiosModule),
+        Box::new(virtualization::VirtualizationModule),
+        Box::new(kernel::Kernel),
+        Box::new(uptime::UptimeModule),
+        Box::new(packages::PackagesModule),
+        Box::new(shell::ShellModule),
+        Box::new(de::DesktopEnvironment),
+        Box::new(wm::WmModule),
+        Box::new(theme::AppearanceModule(theme::Appearance::Theme)),
+        Box::new(theme::AppearanceModule(theme::Appearance::Icons)),
+        Box::new(theme::AppearanceModule(theme::Appearance::Font)),
+        Box::new(theme::AppearanceModule(theme::Appearance::Cursor)),
+        Box::new(terminal::Terminal),
+        Box::new(terminal_font::TerminalFont),
+        Box::new(cpu::CpuModule),
+        Box::new(gpu::GpuModule),
+        Box::new(display::DisplayModule),
+        Box::new(memory::MemoryModule),
+        Box::new(swap::SwapModule),
+        Box::new(disk::DiskModule),
+        Box::new(network::NetworkModule),
+        Box::new(battery::BatteryModule),
+    ]
+}
+
+/// Looks up a built-in module by name.
+pub fn find(name: &str) -> Option<Box<dyn InfoModule>> {
+    registry().into_iter().find(|module| module.name() == name)
+}
+
+/// Names of every built-in module, in registry order.
+pub fn names() -> Vec<&'static str> {
+    registry().iter().map(|module| module.name()).collect()
+}
+
+/// Boxes a collected value.
+pub(crate) fn field<T: Field + 'static>(value: Option<T>) -> Option<Box<dyn Field>> {
+    value.map(|value| Box::new(value) as Box<dyn Field>)
+}
+
+/// Pulls the first version-looking word ("5.2.15") out of a `--version` line.
+pub(crate) fn extract_version(text: &str) -> Option<String> {
+    // Simple regex-like version extraction
+    let words: Vec<&str> = text.split_whitespace().collect();
+    for word in words {
+        if word.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+            if let Some(end) = word.find(|c: char| !c.is_ascii_digit() && c != '.') {
+                return Some(word[..end].to_string());
+            } else if word.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                return Some(word.to_string());
+            }
+        }
+    }
+    None
+}
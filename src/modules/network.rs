@@ -0,0 +1,201 @@
+use serde::{Deserialize, Serialize};
+use std::net::Ipv6Addr;
+use std::path::Path;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_network;
+
+const NET_DIR: &str = "/sys/class/net";
+
+/// `ARPHRD_LOOPBACK` from `<linux/if_arp.h>`, as found in `type`.
+const ARPHRD_LOOPBACK: &str = "772";
+
+/// `IFA_F_TEMPORARY` in `/proc/net/if_inet6`: a privacy address that
+/// changes every few hours.
+const IFA_F_TEMPORARY: u32 = 0x01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceKind {
+    Wired,
+    Wireless,
+    /// Not backed by hardware: bridges, VPN tunnels, container links
+    Virtual,
+}
+
+/// A network interface that is up. Addresses are in CIDR notation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    pub kind: InterfaceKind,
+    /// Negotiated link speed, for wired links
+    pub speed_mbps: Option<u32>,
+    pub ipv4: Vec<String>,
+    pub ipv6: Vec<String>,
+}
+
+/// The interfaces selected by the `[network]` config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Network(pub Vec<NetworkInterface>);
+
+impl Render for Network {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        self.0
+            .iter()
+            .map(|interface| Line::keyed(interface.name.clone(), format_network(interface)))
+            .collect()
+    }
+}
+
+/// `[network]` section of the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Interfaces to show, if up; an empty list shows every wired and
+    /// wireless interface that is up
+    pub interfaces: Vec<String>,
+    /// Show IP addresses; turn off to keep them out of screenshots
+    pub addresses: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            interfaces: Vec::new(),
+            addresses: true,
+        }
+    }
+}
+
+pub struct NetworkModule;
+
+impl InfoModule for NetworkModule {
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    fn label(&self) -> &'static str {
+        "Network"
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        ctx.is_dir(NET_DIR)
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        let network = get_network(ctx);
+        field(Some(network).filter(|network| !network.0.is_empty()))
+    }
+}
+
+fn get_network(ctx: &Context) -> Network {
+    let config = &ctx.config.network;
+    let names = match config.interfaces.is_empty() {
+        true => ctx.read_dir(NET_DIR).unwrap_or_default(),
+        false => config.interfaces.clone(),
+    };
+
+    let (ipv4, ipv6) = match config.addresses {
+        true => (ipv4_addresses(ctx), ipv6_addresses(ctx)),
+        false => (Vec::new(), Vec::new()),
+    };
+    let addresses_of = |addresses: &[(String, String)], name: &str| {
+        addresses
+            .iter()
+            .filter(|(interface, _)| interface == name)
+            .map(|(_, address)| address.clone())
+            .collect()
+    };
+
+    let interfaces = names
+        .into_iter()
+        .filter_map(|name| {
+            let dir = Path::new(NET_DIR).join(&name);
+            let read = |file: &str| ctx.read_to_string(dir.join(file)).map(|content| content.trim().to_string());
+
+            if read("type")? == ARPHRD_LOOPBACK || !is_up(&read("operstate")?, read("flags").as_deref()) {
+                return None;
+            }
+
+            // Physical interfaces link to their bus device
+            let kind = if ctx.exists(dir.join("wireless")) || ctx.exists(dir.join("phy80211")) {
+                InterfaceKind::Wireless
+            } else if ctx.exists(dir.join("device")) {
+                InterfaceKind::Wired
+            } else {
+                InterfaceKind::Virtual
+            };
+            if kind == InterfaceKind::Virtual && config.interfaces.is_empty() {
+                return None;
+            }
+
+            Some(NetworkInterface {
+                kind,
+                // Reads -1 or fails when there is no link
+                speed_mbps: read("speed").and_then(|speed| speed.parse().ok()).filter(|&speed| speed > 0),
+                ipv4: addresses_of(&ipv4, &name),
+                ipv6: addresses_of(&ipv6, &name),
+                name,
+            })
+        })
+        .collect();
+
+    Network(interfaces)
+}
+
+/// Tunnels and some drivers never report an operstate, so "unknown" counts
+/// as up when the interface is administratively up (`IFF_UP`).
+fn is_up(operstate: &str, flags: Option<&str>) -> bool {
+    let administratively_up = || {
+        flags
+            .and_then(|flags| u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok())
+            .is_some_and(|flags| flags & 0x1 != 0)
+    };
+    operstate == "up" || (operstate == "unknown" && administratively_up())
+}
+
+/// IPv4 addresses with their interface, in CIDR notation.
+fn ipv4_addresses(ctx: &Context) -> Vec<(String, String)> {
+    ctx.source()
+        .ipv4_addresses()
+        .into_iter()
+        .map(|address| (address.interface, format!("{}/{}", address.address, address.prefix)))
+        .collect()
+}
+
+/// Global IPv6 addresses with their interface, from `/proc/net/if_inet6`:
+///
+/// ```text
+/// 20010db8000000000000000000000042 02 40 00 80 enp5s0
+/// ```
+///
+/// holds the address, interface index, prefix length, scope and flags in
+/// hex. Link-local and temporary addresses are left out.
+fn ipv6_addresses(ctx: &Context) -> Vec<(String, String)> {
+    let Some(content) = ctx.read_to_string("/proc/net/if_inet6") else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [address, _, prefix, scope, flags, interface] = fields.as_slice() else {
+                return None;
+            };
+            let hex = |field: &str| u32::from_str_radix(field, 16).ok();
+            if hex(scope)? != 0 || hex(flags)? & IFA_F_TEMPORARY != 0 {
+                return None;
+            }
+
+            let address = Ipv6Addr::from(u128::from_str_radix(address, 16).ok()?);
+            Some((interface.to_string(), format!("{}/{}", address, hex(prefix)?)))
+        })
+        .collect()
+}
@@ -0,0 +1,41 @@
+use sysinfo::SystemExt;
+
+use super::{field, Context, Field, InfoModule};
+use crate::distro::OsRelease;
+
+pub struct Os;
+
+impl InfoModule for Os {
+    fn name(&self) -> &'static str {
+        "os"
+    }
+
+    fn label(&self) -> &'static str {
+        "OS"
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_os_name(ctx))
+    }
+}
+
+fn get_os_name(ctx: &Context) -> Option<String> {
+    // Prefer the names from os-release
+    if let Some(os_release) = OsRelease::load_from(ctx.source()) {
+        if let Some(pretty_name) = os_release.pretty_name {
+            return Some(pretty_name);
+        }
+        if let Some(name) = os_release.name {
+            return Some(match os_release.version_id {
+                Some(version) => format!("{} {}", name, version),
+                None => name,
+            });
+        }
+    }
+    
+    let sys = ctx.system()?;
+    match (sys.name(), sys.os_version()) {
+        (Some(name), Some(version)) => Some(format!("{} {}", name, version)),
+        (name, _) => name,
+    }
+}
@@ -0,0 +1,313 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_packages;
+use crate::sqlite;
+
+/// Number of packages installed through one package manager.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageCount {
+    pub manager: String,
+    pub count: usize,
+}
+
+/// Package counts for every package manager found, in detection order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Packages(pub Vec<PackageCount>);
+
+impl Packages {
+    /// Packages installed across all managers.
+    pub fn total(&self) -> usize {
+        self.0.iter().map(|package| package.count).sum()
+    }
+}
+
+impl Render for Packages {
+    fn render(&self, config: &Config) -> Vec<Line> {
+        format_packages(self, config.packages.display).map(Line::new).into_iter().collect()
+    }
+}
+
+/// `[packages]` section of the config file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PackagesConfig {
+    pub display: PackagesDisplay,
+}
+
+/// How the Packages line presents several package managers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackagesDisplay {
+    /// `1852 total`
+    Total,
+    /// `1834 (dpkg), 12 (flatpak), 6 (snap)`
+    #[default]
+    Breakdown,
+    /// `1852 total: 1834 (dpkg), 12 (flatpak), 6 (snap)`
+    Both,
+}
+
+pub struct PackagesModule;
+
+impl InfoModule for PackagesModule {
+    fn name(&self) -> &'static str {
+        "packages"
+    }
+
+    fn label(&self) -> &'static str {
+        "Packages"
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        let packages = get_package_count(ctx);
+        field(Some(packages).filter(|p| !p.0.is_empty()))
+    }
+}
+
+/// Counts the packages installed through one manager, or `None` if it is
+/// not present.
+type Counter = fn(&Context) -> Option<usize>;
+
+fn get_package_count(ctx: &Context) -> Packages {
+    // Package managers and how to count their installed packages
+    let package_managers: [(&str, Counter); 11] = [
+        ("dpkg", count_dpkg),
+        ("rpm", count_rpm),
+        ("pacman", count_pacman),
+        ("apk", count_apk),
+        ("pkg", count_pkg),
+        ("brew", count_brew),
+        ("nix", count_nix),
+        ("flatpak", count_flatpak),
+        ("snap", count_snap),
+        ("portage", count_portage),
+        ("xbps", count_xbps),
+    ];
+    
+    // Most counters only read a file or a directory, but the command
+    // fallbacks can take a while, so run them all at once
+    let found_managers = thread::scope(|scope| {
+        let handles: Vec<_> = package_managers
+            .iter()
+            .map(|&(manager_name, count)| {
+                scope.spawn(move || {
+                    count(ctx).filter(|&count| count > 0).map(|count| PackageCount {
+                        manager: manager_name.to_string(),
+                        count,
+                    })
+                })
+            })
+            .collect();
+        
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().ok().flatten())
+            .collect()
+    });
+    
+    Packages(found_managers)
+}
+
+/// Counts stanzas of `/var/lib/dpkg/status` whose `Status:` is installed.
+/// Removed packages that left config files behind are not counted.
+fn count_dpkg(ctx: &Context) -> Option<usize> {
+    let status = ctx.read_to_string("/var/lib/dpkg/status")?;
+    Some(
+        status
+            .lines()
+            .filter(|line| line.starts_with("Status: ") && line.ends_with(" installed"))
+            .count(),
+    )
+}
+
+fn count_rpm(ctx: &Context) -> Option<usize> {
+    // rpm >= 4.16 keeps its database in SQLite; older Berkeley DB databases
+    // are left to rpm itself
+    let sqlite = Path::new("/var/lib/rpm/rpmdb.sqlite");
+    if ctx.exists(sqlite) {
+        if let Some(count) = sqlite::count_rows(&ctx.source().host_path(sqlite), "Packages") {
+            return Some(count);
+        }
+    }
+    
+    if ctx.exists("/var/lib/rpm") {
+        // rpm can query another root itself
+        let root = ctx.root().to_string_lossy();
+        return count_command_output(ctx, "rpm", &["--root", &root, "-qa"]);
+    }
+    
+    None
+}
+
+fn count_pacman(ctx: &Context) -> Option<usize> {
+    count_dirs(ctx, Path::new("/var/lib/pacman/local"))
+}
+
+fn count_apk(ctx: &Context) -> Option<usize> {
+    let installed = ctx.read_to_string("/lib/apk/db/installed")?;
+    Some(installed.lines().filter(|line| line.starts_with("P:")).count())
+}
+
+fn count_pkg(ctx: &Context) -> Option<usize> {
+    let db = Path::new("/var/db/pkg/local.sqlite");
+    if !ctx.exists(db) {
+        return None;
+    }
+    
+    sqlite::count_rows(&ctx.source().host_path(db), "packages").or_else(|| count_with_command(ctx, "pkg", &["info"]))
+}
+
+fn count_brew(ctx: &Context) -> Option<usize> {
+    let prefixes = ["/opt/homebrew", "/usr/local", "/home/linuxbrew/.linuxbrew"];
+    
+    // Formulae live in Cellar, casks in Caskroom
+    let mut found = false;
+    let mut total = 0;
+    for prefix in prefixes {
+        for dir in ["Cellar", "Caskroom"] {
+            if let Some(count) = count_dirs(ctx, &Path::new(prefix).join(dir)) {
+                found = true;
+                total += count;
+            }
+        }
+    }
+    
+    found.then_some(total)
+}
+
+fn count_nix(ctx: &Context) -> Option<usize> {
+    let mut profiles = vec![
+        PathBuf::from("/run/current-system/sw"),
+        PathBuf::from("/nix/var/nix/profiles/default"),
+    ];
+    if let Some(home) = user_home(ctx) {
+        profiles.push(home.join(".nix-profile"));
+    }
+    
+    let mut found = false;
+    let mut total = 0;
+    for profile in profiles.iter().filter(|profile| ctx.exists(profile)) {
+        // `nix profile` keeps a JSON manifest; profiles managed by nix-env
+        // or NixOS only have a Nix expression, so ask nix-store instead
+        let count = count_nix_manifest(ctx, &profile.join("manifest.json")).or_else(|| {
+            let profile = profile.to_string_lossy();
+            count_with_command(ctx, "nix-store", &["--query", "--requisites", &profile])
+        });
+        
+        if let Some(count) = count {
+            found = true;
+            total += count;
+        }
+    }
+    
+    found.then_some(total)
+}
+
+fn count_nix_manifest(ctx: &Context, path: &Path) -> Option<usize> {
+    let manifest: serde_json::Value = serde_json::from_str(&ctx.read_to_string(path)?).ok()?;
+    // Version 3 and later store elements as an object, earlier versions as
+    // an array
+    match manifest.get("elements")? {
+        serde_json::Value::Array(elements) => Some(elements.len()),
+        serde_json::Value::Object(elements) => Some(elements.len()),
+        _ => None,
+    }
+}
+
+fn count_flatpak(ctx: &Context) -> Option<usize> {
+    let mut installations = vec![PathBuf::from("/var/lib/flatpak")];
+    if let Some(home) = user_home(ctx) {
+        installations.push(home.join(".local/share/flatpak"));
+    }
+    
+    // Apps only, like `flatpak list --app`
+    let counts: Vec<usize> = installations
+        .iter()
+        .filter_map(|installation| count_dirs(ctx, &installation.join("app")))
+        .collect();
+    
+    (!counts.is_empty()).then(|| counts.iter().sum())
+}
+
+fn count_snap(ctx: &Context) -> Option<usize> {
+    // Every installed snap is mounted under /snap/<name>; /snap/bin holds
+    // the command wrappers
+    let entries = ctx.read_dir("/snap")?;
+    Some(
+        entries
+            .iter()
+            .filter(|name| *name != "bin" && ctx.is_dir(Path::new("/snap").join(name)))
+            .count(),
+    )
+}
+
+fn count_portage(ctx: &Context) -> Option<usize> {
+    // /var/db/pkg/<category>/<package-version>
+    let db = Path::new("/var/db/pkg");
+    let categories = ctx.read_dir(db)?;
+    Some(
+        categories
+            .iter()
+            .filter_map(|category| count_dirs(ctx, &db.join(category)))
+            .sum(),
+    )
+}
+
+fn count_xbps(ctx: &Context) -> Option<usize> {
+    // The package database is a plist named after its format version, e.g.
+    // pkgdb-0.38.plist
+    let db = Path::new("/var/db/xbps");
+    let pkgdb = ctx
+        .read_dir(db)?
+        .into_iter()
+        .find(|name| name.starts_with("pkgdb-") && name.ends_with(".plist"));
+    
+    match pkgdb {
+        Some(name) => {
+            let content = ctx.read_to_string(db.join(name))?;
+            Some(content.matches("<string>installed</string>").count())
+        }
+        None => count_with_command(ctx, "xbps-query", &["-l"]),
+    }
+}
+
+/// Counts the subdirectories of `path`, ignoring hidden ones.
+fn count_dirs(ctx: &Context, path: &Path) -> Option<usize> {
+    let entries = ctx.read_dir(path)?;
+    Some(
+        entries
+            .iter()
+            .filter(|name| !name.starts_with('.') && ctx.is_dir(path.join(name)))
+            .count(),
+    )
+}
+
+/// The current user's home directory. Per-user package installations are
+/// only counted on the running system.
+fn user_home(ctx: &Context) -> Option<PathBuf> {
+    if !ctx.is_live() {
+        return None;
+    }
+    ctx.var("HOME").map(PathBuf::from)
+}
+
+/// Counts the non-empty lines printed by a package manager command, for
+/// databases whose format we cannot read directly. Commands query the
+/// running system, so they are not used with `--root`.
+fn count_with_command(ctx: &Context, cmd: &str, args: &[&str]) -> Option<usize> {
+    if !ctx.is_live() {
+        return None;
+    }
+    
+    count_command_output(ctx, cmd, args)
+}
+
+fn count_command_output(ctx: &Context, cmd: &str, args: &[&str]) -> Option<usize> {
+    let output = ctx.run(cmd, args)?;
+    Some(output.lines().filter(|line| !line.trim().is_empty()).count())
+}
@@ -0,0 +1,140 @@
+//! The processes above rustch, read from `/proc`. Shell and terminal
+//! detection walk this chain instead of trusting inherited environment
+//! variables, which go stale under tmux, sudo and nested shells.
+
+use std::path::{Path, PathBuf};
+
+use super::Context;
+
+/// Programs that run another program without being what the user sees,
+/// passed over when walking up from rustch.
+const WRAPPERS: &[&str] = &[
+    "rustch", "sudo", "doas", "su", "run0", "env", "nohup", "nice", "ionice", "time", "timeout", "watch",
+    "strace", "ltrace", "taskset", "chrt", "flock", "xargs",
+];
+
+/// Script interpreters, matched against the start of the executable's name
+/// so that `python3.12` counts.
+const INTERPRETERS: &[&str] = &["python", "perl", "ruby", "node", "lua", "php", "tclsh"];
+
+/// Walks stop here even if the chain is longer, in case of a cycle.
+const MAX_DEPTH: usize = 64;
+
+/// The longest `comm` the kernel keeps; longer names are cut to this.
+const COMM_LEN: usize = 15;
+
+#[derive(Debug, Clone)]
+pub(super) struct Process {
+    pub ppid: u32,
+    /// Name the kernel gave the process, at most [`COMM_LEN`] bytes. For
+    /// scripts this is the script's name rather than the interpreter's.
+    pub comm: String,
+    /// Target of `/proc/<pid>/exe`, which is only readable for our own
+    /// processes unless we are root
+    pub exe: Option<PathBuf>,
+    /// `/proc/<pid>/cmdline`, starting with `argv[0]`
+    pub args: Vec<String>,
+}
+
+impl Process {
+    pub fn load(ctx: &Context, pid: u32) -> Option<Self> {
+        let dir = Path::new("/proc").join(pid.to_string());
+        let (comm, ppid) = parse_stat(&ctx.read_to_string(dir.join("stat"))?)?;
+        let exe = ctx.source().read_link(&dir.join("exe")).map(|exe| {
+            // Set when the binary was replaced by an upgrade
+            let exe = exe.to_string_lossy();
+            PathBuf::from(exe.strip_suffix(" (deleted)").unwrap_or(&exe))
+        });
+        let args = ctx
+            .read_to_string(dir.join("cmdline"))
+            .map(|cmdline| cmdline.split('\0').filter(|arg| !arg.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default();
+
+        Some(Process {
+            ppid,
+            comm,
+            exe,
+            args,
+        })
+    }
+
+    /// The executable's file name, e.g. `python3` for a Python script.
+    pub fn exe_name(&self) -> Option<&str> {
+        self.exe.as_deref()?.file_name()?.to_str()
+    }
+
+    /// `comm`, completed from the executable's name when the kernel cut it
+    /// short (`gnome-terminal-` for `gnome-terminal-server`).
+    pub fn name(&self) -> &str {
+        match self.exe_name() {
+            Some(exe) if self.comm.len() == COMM_LEN && exe.starts_with(&self.comm) => exe,
+            _ => &self.comm,
+        }
+    }
+
+    /// Whether the process only runs another one: sudo and similar, or an
+    /// interpreter running a script.
+    pub fn is_wrapper(&self) -> bool {
+        let interpreter = self
+            .exe_name()
+            .is_some_and(|exe| INTERPRETERS.iter().any(|interpreter| exe.starts_with(interpreter)));
+        interpreter || WRAPPERS.contains(&self.name())
+    }
+}
+
+/// Parses `/proc/<pid>/stat`: "4242 (rustch) S 4100 ...". The name can
+/// hold spaces and parentheses, so it ends at the last `)`.
+fn parse_stat(stat: &str) -> Option<(String, u32)> {
+    let start = stat.find('(')?;
+    let end = stat.rfind(')')?;
+    let comm = stat.get(start + 1..end)?.to_string();
+    let ppid = stat[end + 1..].split_whitespace().nth(1)?.parse().ok()?;
+    Some((comm, ppid))
+}
+
+/// The parents of `pid`, nearest first, up to but not including init.
+/// Empty where `/proc` is not available.
+pub(super) fn ancestors(ctx: &Context, pid: u32) -> Vec<Process> {
+    let mut chain = Vec::new();
+    let mut next = Process::load(ctx, pid).map(|process| process.ppid);
+    while let Some(ppid) = next.filter(|&ppid| ppid > 1 && chain.len() < MAX_DEPTH) {
+        let Some(process) = Process::load(ctx, ppid) else {
+            break;
+        };
+        next = Some(process.ppid);
+        chain.push(process);
+    }
+    chain
+}
+
+/// The real user id in `/proc/<pid>/status`.
+fn uid(ctx: &Context, pid: u32) -> Option<u32> {
+    let status = ctx.read_to_string(format!("/proc/{}/status", pid))?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// The pid and `comm` of every process of the user running rustch, or of
+/// the user who ran `sudo rustch`. All processes when the user is unknown.
+pub(super) fn user_processes(ctx: &Context) -> Vec<(u32, String)> {
+    let user = ctx
+        .var("SUDO_UID")
+        .and_then(|uid| uid.parse().ok())
+        .or_else(|| uid(ctx, ctx.source().pid()));
+
+    ctx.read_dir("/proc")
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| entry.parse::<u32>().ok())
+        .filter(|&pid| user.is_none() || uid(ctx, pid) == user)
+        .filter_map(|pid| {
+            let comm = ctx.read_to_string(format!("/proc/{}/comm", pid))?;
+            Some((pid, comm.trim_end().to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,75 @@
+use serde::Serialize;
+
+use super::process::{ancestors, Process};
+use super::{extract_version, field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_shell;
+
+const SHELLS: &[&str] = &[
+    "bash", "zsh", "fish", "sh", "dash", "ash", "ksh", "mksh", "oksh", "tcsh", "csh", "nu", "elvish", "xonsh",
+    "pwsh", "ion", "osh", "yash",
+];
+
+/// The user's shell and, when it reports one, its version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Shell {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl Render for Shell {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        vec![Line::new(format_shell(self))]
+    }
+}
+
+pub struct ShellModule;
+
+impl InfoModule for ShellModule {
+    fn name(&self) -> &'static str {
+        "shell"
+    }
+
+    fn label(&self) -> &'static str {
+        "Shell"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_shell(ctx))
+    }
+}
+
+fn get_shell(ctx: &Context) -> Option<Shell> {
+    // The nearest shell above us, past sudo, scripts and the like; $SHELL
+    // is only the login shell, which may not be the one running
+    let name = ancestors(ctx, ctx.source().pid())
+        .iter()
+        .find(|process| is_interactive_shell(process))
+        .map(|process| process.name().to_string())
+        .or_else(|| {
+            let shell = ctx.var("SHELL")?;
+            shell.rsplit('/').next().filter(|name| !name.is_empty()).map(str::to_string)
+        })?;
+
+    let version = ctx
+        .run(&name, &["--version"])
+        .and_then(|output| output.lines().next().and_then(extract_version));
+    Some(Shell { name, version })
+}
+
+/// Whether `name` is a known shell.
+pub(super) fn is_shell(name: &str) -> bool {
+    // Login shells are started as "-bash"
+    SHELLS.contains(&name.trim_start_matches('-'))
+}
+
+/// A shell the user typed into, as opposed to one running a script
+/// (`bash build.sh`) or a command line (`sh -c ...`).
+fn is_interactive_shell(process: &Process) -> bool {
+    let runs_script = process.args.iter().skip(1).any(|arg| arg == "-c" || !arg.starts_with('-'));
+    is_shell(process.name()) && !runs_script
+}
@@ -0,0 +1,168 @@
+use serde::Serialize;
+use std::path::Path;
+use sysinfo::SystemExt;
+
+use super::memory::Meminfo;
+use super::{field, Context, Field, InfoModule, Line, Render, Subsystem};
+use crate::config::Config;
+use crate::format::format_swap;
+
+/// What backs a swap area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwapKind {
+    Partition,
+    File,
+    /// Compressed RAM, see zram(4)
+    Zram,
+}
+
+/// One active swap area from `/proc/swaps`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SwapDevice {
+    pub path: String,
+    pub kind: SwapKind,
+    pub used_bytes: u64,
+    pub total_bytes: u64,
+    /// Uncompressed size of the stored data over its compressed size, for
+    /// zram devices holding data
+    pub compression_ratio: Option<f64>,
+}
+
+/// Swap usage. `devices` is empty when the system does not list its swap
+/// areas (outside Linux).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Swap {
+    pub used_bytes: u64,
+    pub total_bytes: u64,
+    pub devices: Vec<SwapDevice>,
+}
+
+impl Render for Swap {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        let (zram, disk): (Vec<&SwapDevice>, Vec<&SwapDevice>) =
+            self.devices.iter().partition(|device| device.kind == SwapKind::Zram);
+
+        // zram is RAM, not disk, so it gets a line of its own
+        if zram.is_empty() {
+            return vec![Line::new(format_swap(self.used_bytes, self.total_bytes, None))];
+        }
+
+        let mut lines = Vec::new();
+        if !disk.is_empty() {
+            let (used, total) = sum(&disk);
+            lines.push(Line::keyed("disk", format_swap(used, total, None)));
+        }
+
+        let (used, total) = sum(&zram);
+        let original: f64 = zram
+            .iter()
+            .filter_map(|device| Some(device.compression_ratio? * device.used_bytes as f64))
+            .sum();
+        let ratio = (used > 0 && original > 0.0).then(|| original / used as f64);
+        lines.push(Line::keyed("zram", format_swap(used, total, ratio)));
+
+        lines
+    }
+}
+
+fn sum(devices: &[&SwapDevice]) -> (u64, u64) {
+    devices
+        .iter()
+        .fold((0, 0), |(used, total), device| (used + device.used_bytes, total + device.total_bytes))
+}
+
+pub struct SwapModule;
+
+impl InfoModule for SwapModule {
+    fn name(&self) -> &'static str {
+        "swap"
+    }
+
+    fn label(&self) -> &'static str {
+        "Swap"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_swap(ctx))
+    }
+}
+
+fn get_swap(ctx: &Context) -> Option<Swap> {
+    if let Some(swaps) = ctx.read_to_string("/proc/swaps") {
+        let devices = parse_swaps(ctx, &swaps);
+
+        // /proc/meminfo has the totals even while swapon is racing us
+        let (used_bytes, total_bytes) = match Meminfo::load(ctx) {
+            Some(meminfo) => (meminfo.swap_total.saturating_sub(meminfo.swap_free), meminfo.swap_total),
+            None => sum(&devices.iter().collect::<Vec<_>>()),
+        };
+
+        return Some(Swap {
+            used_bytes,
+            total_bytes,
+            devices,
+        });
+    }
+
+    let sys = ctx.refreshed(Subsystem::Memory)?;
+    Some(Swap {
+        used_bytes: sys.used_swap(),
+        total_bytes: sys.total_swap(),
+        devices: Vec::new(),
+    })
+}
+
+/// Parses `/proc/swaps`, whose sizes are in KiB:
+///
+/// ```text
+/// Filename        Type        Size     Used  Priority
+/// /dev/zram0      partition   8388604  1024  100
+/// /swapfile       file        2097148  0     -2
+/// ```
+fn parse_swaps(ctx: &Context, content: &str) -> Vec<SwapDevice> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [path, kind, size, used, ..] = fields.as_slice() else {
+                return None;
+            };
+
+            // Spaces in file names are escaped as \040
+            let path = path.replace("\\040", " ");
+            let zram = path
+                .strip_prefix("/dev/")
+                .filter(|name| name.starts_with("zram"));
+            let kind = match (zram, *kind) {
+                (Some(_), _) => SwapKind::Zram,
+                (None, "file") => SwapKind::File,
+                _ => SwapKind::Partition,
+            };
+
+            Some(SwapDevice {
+                compression_ratio: zram.and_then(|name| zram_compression_ratio(ctx, name)),
+                kind,
+                used_bytes: used.parse::<u64>().ok()? * 1024,
+                total_bytes: size.parse::<u64>().ok()? * 1024,
+                path,
+            })
+        })
+        .collect()
+}
+
+/// Reads `orig_data_size` and `compr_data_size`, the first two fields of
+/// `/sys/block/<zram>/mm_stat`.
+fn zram_compression_ratio(ctx: &Context, name: &str) -> Option<f64> {
+    let mm_stat = ctx.read_to_string(Path::new("/sys/block").join(name).join("mm_stat"))?;
+    let mut fields = mm_stat.split_whitespace().map(|field| field.parse::<u64>().ok());
+    let original = fields.next()??;
+    let compressed = fields.next()??;
+
+    (original > 0 && compressed > 0).then(|| original as f64 / compressed as f64)
+}
@@ -0,0 +1,165 @@
+use super::process::ancestors;
+use super::shell::is_shell;
+use super::{field, Context, Field, InfoModule};
+
+/// Process names of terminal emulators.
+const TERMINALS: &[(&str, &str)] = &[
+    ("gnome-terminal-server", "GNOME Terminal"),
+    ("kgx", "GNOME Console"),
+    ("konsole", "Konsole"),
+    ("xfce4-terminal", "Xfce Terminal"),
+    ("lxterminal", "LXTerminal"),
+    ("mate-terminal", "MATE Terminal"),
+    ("terminator", "Terminator"),
+    ("tilix", "Tilix"),
+    ("alacritty", "Alacritty"),
+    ("kitty", "kitty"),
+    ("urxvt", "rxvt-unicode"),
+    ("urxvtd", "rxvt-unicode"),
+    ("rxvt", "rxvt"),
+    ("xterm", "xterm"),
+    ("st", "st"),
+    ("foot", "foot"),
+    ("wezterm-gui", "WezTerm"),
+    ("ghostty", "Ghostty"),
+];
+
+/// `TERM` values that only one terminal sets.
+const TERMINFO: &[(&str, &str)] = &[
+    ("xterm-kitty", "kitty"),
+    ("alacritty", "Alacritty"),
+    ("foot", "foot"),
+    ("foot-extra", "foot"),
+    ("wezterm", "WezTerm"),
+    ("xterm-ghostty", "Ghostty"),
+];
+
+/// Processes above the shell that mean there is no terminal emulator to
+/// find, such as an SSH login.
+const SESSION_ROOTS: &[&str] = &[
+    "sshd", "sshd-session", "dropbear", "mosh-server", "login", "agetty", "getty", "systemd", "init",
+];
+
+pub struct Terminal;
+
+impl InfoModule for Terminal {
+    fn name(&self) -> &'static str {
+        "terminal"
+    }
+
+    fn label(&self) -> &'static str {
+        "Terminal"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_terminal(ctx))
+    }
+}
+
+pub(super) fn get_terminal(ctx: &Context) -> Option<String> {
+    // The process tree is right even where inherited variables went stale,
+    // e.g. in a tmux session started from another terminal
+    let process = terminal_process(ctx);
+    if let Some(name) = process.as_deref().and_then(known_terminal) {
+        return Some(name.to_string());
+    }
+    
+    // Check terminal-specific environment variables
+    if let Some(term) = ctx.var("TERM_PROGRAM") {
+        if !term.is_empty() && term != "tmux" {
+            return Some(normalize_terminal_name(&term));
+        }
+    }
+    
+    // Check other terminal variables
+    let term_vars = vec![
+        "KONSOLE_VERSION",
+        "GNOME_TERMINAL_SCREEN",
+        "TERMINATOR_UUID",
+        "KITTY_WINDOW_ID",
+        "ALACRITTY_SOCKET",
+        "ALACRITTY_WINDOW_ID",
+        "WEZTERM_PANE",
+        "WT_SESSION",
+    ];
+    
+    for var in term_vars {
+        if ctx.var(var).is_some() {
+            let name = match var {
+                "KONSOLE_VERSION" => "Konsole",
+                "GNOME_TERMINAL_SCREEN" => "GNOME Terminal",
+                "TERMINATOR_UUID" => "Terminator",
+                "KITTY_WINDOW_ID" => "kitty",
+                "ALACRITTY_SOCKET" | "ALACRITTY_WINDOW_ID" => "Alacritty",
+                "WEZTERM_PANE" => "WezTerm",
+                "WT_SESSION" => "Windows Terminal",
+                _ => continue,
+            };
+            return Some(name.to_string());
+        }
+    }
+    
+    // Terminals that export nothing else, such as foot, name their own
+    // terminfo entry
+    let term = ctx.var("TERM");
+    if let Some(name) = term.as_deref().and_then(terminfo_terminal) {
+        return Some(name.to_string());
+    }
+
+    // An unknown program hosting the shell, such as an editor's terminal,
+    // then the TERM variable
+    process.or(term)
+}
+
+fn normalize_terminal_name(name: &str) -> String {
+    match name.to_lowercase().as_str() {
+        "apple_terminal" => "Terminal.app".to_string(),
+        "iterm.app" => "iTerm2".to_string(),
+        "hyper" => "Hyper".to_string(),
+        "vscode" => "VS Code".to_string(),
+        _ => name.to_string(),
+    }
+}
+
+fn terminfo_terminal(term: &str) -> Option<&'static str> {
+    TERMINFO.iter().find(|(name, _)| *name == term).map(|(_, terminal)| *terminal)
+}
+
+fn known_terminal(process: &str) -> Option<&'static str> {
+    TERMINALS.iter().find(|(name, _)| *name == process).map(|(_, terminal)| *terminal)
+}
+
+/// The name of the first process above our shells and wrappers. The tmux
+/// server is a daemon, so inside tmux the walk continues from the client
+/// attached to it.
+fn terminal_process(ctx: &Context) -> Option<String> {
+    let mut chain = ancestors(ctx, ctx.source().pid()).into_iter();
+    let mut in_tmux = false;
+    while let Some(process) = chain.next() {
+        let name = process.name();
+        if !in_tmux && (name == "tmux" || name.starts_with("tmux: ")) {
+            in_tmux = true;
+            // tmux may be off PATH, on another socket (-L, -S) or another
+            // user's; then carry on above the server
+            let client = ctx
+                .run("tmux", &["display-message", "-p", "#{client_pid}"])
+                .and_then(|client| client.trim().parse().ok());
+            if let Some(client) = client {
+                chain = ancestors(ctx, client).into_iter();
+            }
+            continue;
+        }
+        if known_terminal(name).is_some() {
+            return Some(name.to_string());
+        }
+        if is_shell(name) || process.is_wrapper() {
+            continue;
+        }
+        return (!SESSION_ROOTS.contains(&name)).then(|| name.to_string());
+    }
+    None
+}
@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+
+use super::terminal::get_terminal;
+use super::theme::{config_home, data_home, ini_value, unquote, Font};
+use super::{field, Context, Field, InfoModule};
+
+pub struct TerminalFont;
+
+impl InfoModule for TerminalFont {
+    fn name(&self) -> &'static str {
+        "terminal_font"
+    }
+
+    fn label(&self) -> &'static str {
+        "Terminal Font"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_terminal_font(ctx))
+    }
+}
+
+fn get_terminal_font(ctx: &Context) -> Option<Font> {
+    let terminal = get_terminal(ctx)?;
+    let config = config_home(ctx)?;
+    let home = PathBuf::from(ctx.var("HOME")?);
+
+    match terminal.as_str() {
+        "kitty" => {
+            let dir = ctx
+                .var("KITTY_CONFIG_DIRECTORY")
+                .map_or_else(|| config.join("kitty"), PathBuf::from);
+            kitty_font(&ctx.read_to_string(dir.join("kitty.conf"))?)
+        }
+        "Alacritty" => {
+            let read = |file: &str| {
+                [config.join("alacritty").join(file), config.join(file), home.join(format!(".{}", file))]
+                    .iter()
+                    .find_map(|path| ctx.read_to_string(path))
+            };
+            // The TOML config replaced the YAML one in Alacritty 0.13
+            match read("alacritty.toml") {
+                Some(content) => alacritty_toml_font(&content),
+                None => alacritty_yaml_font(&read("alacritty.yml")?),
+            }
+        }
+        "foot" => foot_font(&ctx.read_to_string(config.join("foot/foot.ini"))?),
+        "WezTerm" => {
+            let content = ctx
+                .read_to_string(config.join("wezterm/wezterm.lua"))
+                .or_else(|| ctx.read_to_string(home.join(".wezterm.lua")))?;
+            wezterm_font(&content)
+        }
+        "Konsole" => {
+            let profile = ini_value(&ctx.read_to_string(config.join("konsolerc"))?, Some("Desktop Entry"), "DefaultProfile")?;
+            let profile = ctx.read_to_string(data_home(ctx)?.join("konsole").join(profile))?;
+            Font::parse_qt(&ini_value(&profile, Some("Appearance"), "Font")?)
+        }
+        "Xfce Terminal" => {
+            let terminalrc = ctx.read_to_string(config.join("xfce4/terminal/terminalrc"))?;
+            match ini_value(&terminalrc, Some("Configuration"), "FontUseSystem").as_deref() {
+                Some("TRUE") => xfce_monospace_font(ctx),
+                _ => Font::parse_pango(&ini_value(&terminalrc, Some("Configuration"), "FontName")?),
+            }
+        }
+        "GNOME Terminal" => gnome_terminal_font(ctx),
+        _ => None,
+    }
+}
+
+/// Reads `font_family` and `font_size` from kitty.conf, whose lines are a
+/// key and a value separated by spaces. kitty's default size is 11pt.
+fn kitty_font(content: &str) -> Option<Font> {
+    let value = |key: &str| {
+        content.lines().rev().find_map(|line| {
+            let (name, value) = line.trim().split_once(char::is_whitespace)?;
+            (name == key).then(|| value.trim().to_string())
+        })
+    };
+
+    Some(Font {
+        family: value("font_family")?,
+        size: Some(value("font_size").and_then(|size| size.parse().ok()).unwrap_or(11.0)),
+    })
+}
+
+/// Reads `[font.normal] family` and `[font] size` from alacritty.toml.
+fn alacritty_toml_font(content: &str) -> Option<Font> {
+    let config: toml::Table = content.parse().ok()?;
+    let font = config.get("font")?;
+    Some(Font {
+        family: font.get("normal")?.get("family")?.as_str()?.to_string(),
+        size: font
+            .get("size")
+            .and_then(|size| size.as_float().or_else(|| size.as_integer().map(|size| size as f64))),
+    })
+}
+
+/// Reads `font.normal.family` and `font.size` from alacritty.yml, following
+/// the nesting by indentation.
+fn alacritty_yaml_font(content: &str) -> Option<Font> {
+    let mut path: Vec<(usize, &str)> = Vec::new();
+    let mut family = None;
+    let mut size = None;
+
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or_default().trim_end();
+        let indent = line.len() - line.trim_start().len();
+        let Some((key, value)) = line.trim_start().split_once(':') else {
+            continue;
+        };
+        if key.starts_with('#') || key.starts_with('-') {
+            continue;
+        }
+
+        while path.last().is_some_and(|(level, _)| *level >= indent) {
+            path.pop();
+        }
+        let value = value.trim();
+        if value.is_empty() {
+            path.push((indent, key));
+            continue;
+        }
+
+        let keys: Vec<&str> = path.iter().map(|(_, key)| *key).chain([key]).collect();
+        match keys.as_slice() {
+            ["font", "normal", "family"] => family = unquote(value),
+            ["font", "size"] => size = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some(Font { family: family?, size })
+}
+
+/// Reads `[main] font` from foot.ini: fontconfig patterns such as
+/// "JetBrains Mono:size=11", the first one being the primary font. foot
+/// defaults to "monospace:size=8".
+fn foot_font(content: &str) -> Option<Font> {
+    let fonts = ini_value(content, Some("main"), "font").or_else(|| ini_value(content, None, "font"))?;
+    let mut parts = fonts.split(',').next()?.split(':');
+    let family = parts.next()?.trim();
+    let size = parts
+        .find_map(|attribute| attribute.trim().strip_prefix("size="))
+        .and_then(|size| size.parse().ok());
+
+    (!family.is_empty()).then(|| Font {
+        family: family.to_string(),
+        size: size.or(Some(8.0)),
+    })
+}
+
+/// Finds the common ways of setting the font in wezterm.lua:
+///
+/// ```text
+/// config.font = wezterm.font("JetBrains Mono")
+/// config.font = wezterm.font_with_fallback({ "Fira Code", "Noto Color Emoji" })
+/// config.font = wezterm.font({ family = "Iosevka", weight = "Bold" })
+/// config.font_size = 12.0
+/// ```
+///
+/// The first string after `wezterm.font` is taken as the family; anything
+/// computed in Lua is out of reach. WezTerm's default size is 12pt.
+fn wezterm_font(content: &str) -> Option<Font> {
+    let lines = || content.lines().map(str::trim).filter(|line| !line.starts_with("--"));
+
+    let family = lines().find_map(|line| {
+        let call = &line[line.find("wezterm.font")?..];
+        let start = call.find(['"', '\''])?;
+        let quote = call[start..].chars().next()?;
+        let rest = &call[start + 1..];
+        Some(rest[..rest.find(quote)?].to_string())
+    })?;
+    let size = lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        if !key.trim().ends_with("font_size") {
+            return None;
+        }
+        value.trim().trim_end_matches(',').parse().ok()
+    });
+
+    Some(Font {
+        family,
+        size: size.or(Some(12.0)),
+    })
+}
+
+/// The font of GNOME Terminal's default profile, from `dconf dump`:
+///
+/// ```text
+/// [/]
+/// default='b1dcc9dd-5262-4d8d-a863-c897e6d979b9'
+///
+/// [:b1dcc9dd-5262-4d8d-a863-c897e6d979b9]
+/// font='Fira Code 12'
+/// use-system-font=false
+/// ```
+///
+/// Profiles use the desktop's monospace font unless told otherwise.
+fn gnome_terminal_font(ctx: &Context) -> Option<Font> {
+    let dump = ctx.run("dconf", &["dump", "/org/gnome/terminal/legacy/profiles:/"]).unwrap_or_default();
+    let profile = ini_value(&dump, Some("/"), "default").or_else(|| {
+        dump.lines()
+            .find_map(|line| line.strip_prefix("[:")?.strip_suffix(']'))
+            .map(str::to_string)
+    });
+    let group = profile.map(|profile| format!(":{}", profile));
+    let value = |key: &str| ini_value(&dump, Some(group.as_deref()?), key);
+
+    match value("use-system-font").as_deref() {
+        Some("false") => Font::parse_pango(&value("font")?),
+        _ => gnome_monospace_font(ctx),
+    }
+}
+
+fn gnome_monospace_font(ctx: &Context) -> Option<Font> {
+    let font = ctx.gsettings("org.gnome.desktop.interface", "monospace-font-name")?;
+    Font::parse_pango(&unquote(&font)?)
+}
+
+/// Xfce's monospace font, which its settings daemon hands to GTK as
+/// `Gtk/MonospaceFontName`.
+fn xfce_monospace_font(ctx: &Context) -> Option<Font> {
+    let font = ctx.run("xfconf-query", &["-c", "xsettings", "-p", "/Gtk/MonospaceFontName"])?;
+    Font::parse_pango(&font)
+}
@@ -0,0 +1,273 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+use super::de::{get_desktop_environment, has_graphical_session};
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::{format_font, format_toolkits};
+
+/// A font family and its size in points.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Font {
+    pub family: String,
+    pub size: Option<f64>,
+}
+
+impl Font {
+    /// Parses a Pango font description such as "Cantarell Bold 11", as used
+    /// by GTK and GNOME. The size is the last word, optionally after a comma
+    /// ("Noto Sans, 10").
+    pub(super) fn parse_pango(description: &str) -> Option<Font> {
+        let description = description.trim();
+        let (family, size) = match description.rsplit_once(' ') {
+            Some((family, size)) => match size.parse::<f64>() {
+                Ok(size) => (family.trim().trim_end_matches(',').trim_end(), Some(size)),
+                Err(_) => (description, None),
+            },
+            None => (description, None),
+        };
+        (!family.is_empty()).then(|| Font {
+            family: family.to_string(),
+            size,
+        })
+    }
+
+    /// Parses a Qt font string such as "Noto Sans,10,-1,5,50,0,0,0,0,0",
+    /// whose first two fields are the family and point size.
+    pub(super) fn parse_qt(description: &str) -> Option<Font> {
+        let mut fields = description.split(',');
+        let family = fields.next()?.trim();
+        (!family.is_empty()).then(|| Font {
+            family: family.to_string(),
+            size: fields.next().and_then(|size| size.trim().parse().ok()).filter(|&size| size > 0.0),
+        })
+    }
+}
+
+impl Render for Font {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        vec![Line::new(format_font(self))]
+    }
+}
+
+/// A setting as configured for GTK and for Qt applications. Desktops other
+/// than KDE Plasma only configure GTK.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Toolkits<T> {
+    pub gtk: Option<T>,
+    pub qt: Option<T>,
+}
+
+impl Render for Toolkits<String> {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        vec![Line::new(format_toolkits(self.gtk.clone(), self.qt.clone()))]
+    }
+}
+
+impl Render for Toolkits<Font> {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        let gtk = self.gtk.as_ref().map(format_font);
+        let qt = self.qt.as_ref().map(format_font);
+        vec![Line::new(format_toolkits(gtk, qt))]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Appearance {
+    Theme,
+    Icons,
+    Font,
+    Cursor,
+}
+
+impl Appearance {
+    /// Key of the `*.desktop.interface` gsettings schemas.
+    fn gsettings_key(self) -> &'static str {
+        match self {
+            Appearance::Theme => "gtk-theme",
+            Appearance::Icons => "icon-theme",
+            Appearance::Font => "font-name",
+            Appearance::Cursor => "cursor-theme",
+        }
+    }
+
+    /// Key of GTK's `settings.ini` and `.gtkrc-2.0`.
+    fn gtk_key(self) -> &'static str {
+        match self {
+            Appearance::Theme => "gtk-theme-name",
+            Appearance::Icons => "gtk-icon-theme-name",
+            Appearance::Font => "gtk-font-name",
+            Appearance::Cursor => "gtk-cursor-theme-name",
+        }
+    }
+
+    /// File, group and key of the KDE setting.
+    fn kde_key(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            Appearance::Theme => ("kdeglobals", "KDE", "widgetStyle"),
+            Appearance::Icons => ("kdeglobals", "Icons", "Theme"),
+            Appearance::Font => ("kdeglobals", "General", "font"),
+            Appearance::Cursor => ("kcminputrc", "Mouse", "cursorTheme"),
+        }
+    }
+}
+
+/// The Theme, Icons, Font and Cursor modules, which read the same settings
+/// files.
+pub(super) struct AppearanceModule(pub Appearance);
+
+impl InfoModule for AppearanceModule {
+    fn name(&self) -> &'static str {
+        match self.0 {
+            Appearance::Theme => "theme",
+            Appearance::Icons => "icons",
+            Appearance::Font => "font",
+            Appearance::Cursor => "cursor",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self.0 {
+            Appearance::Theme => "Theme",
+            Appearance::Icons => "Icons",
+            Appearance::Font => "Font",
+            Appearance::Cursor => "Cursor",
+        }
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        // macOS has neither GTK nor KDE settings
+        ctx.source().os() != "macos" && has_graphical_session(ctx)
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        let desktop = desktop_family(ctx);
+        let gtk = gtk_setting(ctx, self.0, desktop);
+        let qt = match desktop {
+            Some(Desktop::Kde) => kde_setting(ctx, self.0),
+            _ => None,
+        };
+        if gtk.is_none() && qt.is_none() {
+            return None;
+        }
+
+        match self.0 {
+            Appearance::Font => field(Some(Toolkits {
+                gtk: gtk.as_deref().and_then(Font::parse_pango),
+                qt: qt.as_deref().and_then(Font::parse_qt),
+            })),
+            _ => field(Some(Toolkits { gtk, qt })),
+        }
+    }
+}
+
+/// Desktops whose settings we know where to find.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Desktop {
+    /// GNOME and the desktops built on its settings: Budgie, Pantheon, Unity
+    Gnome,
+    Cinnamon,
+    Mate,
+    Kde,
+}
+
+fn desktop_family(ctx: &Context) -> Option<Desktop> {
+    // XDG_CURRENT_DESKTOP values such as "X-Cinnamon" are not normalized
+    let desktop = get_desktop_environment(ctx)?.to_lowercase();
+    let is = |names: &[&str]| names.iter().any(|name| desktop.contains(name));
+    if is(&["kde"]) {
+        Some(Desktop::Kde)
+    } else if is(&["cinnamon"]) {
+        Some(Desktop::Cinnamon)
+    } else if is(&["mate"]) {
+        Some(Desktop::Mate)
+    } else if is(&["gnome", "budgie", "pantheon", "unity"]) {
+        Some(Desktop::Gnome)
+    } else {
+        None
+    }
+}
+
+/// The GTK setting. GNOME and the desktops built on it keep their settings
+/// in dconf, and write the GTK files only for applications outside the
+/// desktop; everything else configures GTK through its files, newest GTK
+/// first.
+fn gtk_setting(ctx: &Context, setting: Appearance, desktop: Option<Desktop>) -> Option<String> {
+    let schema = match (desktop, setting) {
+        (Some(Desktop::Gnome), _) => Some("org.gnome.desktop.interface"),
+        (Some(Desktop::Cinnamon), _) => Some("org.cinnamon.desktop.interface"),
+        (Some(Desktop::Mate), Appearance::Cursor) => Some("org.mate.peripherals-mouse"),
+        (Some(Desktop::Mate), _) => Some("org.mate.interface"),
+        _ => None,
+    };
+    let gsettings = schema.and_then(|schema| unquote(&ctx.gsettings(schema, setting.gsettings_key())?));
+
+    gsettings.or_else(|| {
+        let config = config_home(ctx)?;
+        let ini = ["gtk-4.0/settings.ini", "gtk-3.0/settings.ini"]
+            .iter()
+            .find_map(|file| ini_value(&ctx.read_to_string(config.join(file))?, Some("Settings"), setting.gtk_key()));
+        let home = PathBuf::from(ctx.var("HOME")?);
+        ini.or_else(|| ini_value(&ctx.read_to_string(home.join(".gtkrc-2.0"))?, None, setting.gtk_key()))
+    })
+}
+
+fn kde_setting(ctx: &Context, setting: Appearance) -> Option<String> {
+    let (file, group, key) = setting.kde_key();
+    ini_value(&ctx.read_to_string(config_home(ctx)?.join(file))?, Some(group), key)
+}
+
+/// `$XDG_CONFIG_HOME`, or `~/.config` when it is unset.
+pub(super) fn config_home(ctx: &Context) -> Option<PathBuf> {
+    match ctx.var("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => ctx.var("HOME").map(|home| PathBuf::from(home).join(".config")),
+    }
+}
+
+/// `$XDG_DATA_HOME`, or `~/.local/share` when it is unset.
+pub(super) fn data_home(ctx: &Context) -> Option<PathBuf> {
+    match ctx.var("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => ctx.var("HOME").map(|home| PathBuf::from(home).join(".local/share")),
+    }
+}
+
+/// Looks up `key` in an INI-style file. With `group` set, only that
+/// `[group]` is searched; otherwise only the lines before the first group
+/// are. Quotes around the value are removed.
+pub(super) fn ini_value(content: &str, group: Option<&str>, key: &str) -> Option<String> {
+    let mut current: Option<&str> = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            current = Some(name);
+            continue;
+        }
+        if current != group || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        if name.trim() == key {
+            return unquote(value.trim());
+        }
+    }
+    None
+}
+
+/// Strips the quotes of `"value"` and of gsettings' `'value'`; empty values
+/// are unset.
+pub(super) fn unquote(value: &str) -> Option<String> {
+    let value = ['"', '\'']
+        .iter()
+        .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+        .unwrap_or(value);
+    (!value.is_empty()).then(|| value.to_string())
+}
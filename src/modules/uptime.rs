@@ -0,0 +1,56 @@
+use serde::{Serialize, Serializer};
+use std::time::Duration;
+use sysinfo::SystemExt;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_uptime;
+
+/// Time since boot. Serialized to JSON as whole seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Uptime(pub Duration);
+
+impl Serialize for Uptime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0.as_secs())
+    }
+}
+
+impl Render for Uptime {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        vec![Line::new(format_uptime(self.0))]
+    }
+}
+
+pub struct UptimeModule;
+
+impl InfoModule for UptimeModule {
+    fn name(&self) -> &'static str {
+        "uptime"
+    }
+
+    fn label(&self) -> &'static str {
+        "Uptime"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_uptime(ctx))
+    }
+}
+
+fn get_uptime(ctx: &Context) -> Option<Uptime> {
+    // "<seconds since boot> <idle seconds>"
+    let from_proc = ctx.read_to_string("/proc/uptime").and_then(|content| {
+        let seconds: f64 = content.split_whitespace().next()?.parse().ok()?;
+        Some(Duration::from_secs(seconds as u64))
+    });
+    
+    match from_proc {
+        Some(uptime) => Some(Uptime(uptime)),
+        None => Some(Uptime(Duration::from_secs(ctx.system()?.uptime()))),
+    }
+}
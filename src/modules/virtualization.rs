@@ -0,0 +1,190 @@
+use serde::Serialize;
+
+use super::{field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+
+/// Substrings of DMI vendor and product strings set by hypervisors, checked
+/// in order.
+const DMI_VENDORS: &[(&str, &str)] = &[
+    ("KVM", "KVM"),
+    ("Amazon EC2", "Amazon EC2"),
+    ("Google Compute Engine", "Google Compute Engine"),
+    ("QEMU", "QEMU"),
+    ("VMware", "VMware"),
+    ("VMW", "VMware"),
+    ("innotek GmbH", "VirtualBox"),
+    ("VirtualBox", "VirtualBox"),
+    ("Xen", "Xen"),
+    ("Bochs", "Bochs"),
+    ("Parallels", "Parallels"),
+    ("BHYVE", "bhyve"),
+    ("Apple Virtualization", "Apple Virtualization"),
+    ("Virtual Machine", "Hyper-V"),
+];
+
+/// The DMI files searched for [`DMI_VENDORS`].
+const DMI_FILES: &[&str] = &["product_name", "sys_vendor", "board_vendor", "bios_vendor"];
+
+/// Container managers as named by the `container` variable of PID 1 and
+/// `/run/systemd/container`.
+const CONTAINER_MANAGERS: &[(&str, &str)] = &[
+    ("docker", "Docker"),
+    ("podman", "Podman"),
+    ("lxc", "LXC"),
+    ("lxc-libvirt", "LXC"),
+    ("systemd-nspawn", "systemd-nspawn"),
+    ("rkt", "rkt"),
+    ("oci", "OCI"),
+    ("proot", "proot"),
+    ("pouch", "Pouch"),
+];
+
+/// Cgroup path fragments of container runtimes, checked in order.
+const CGROUP_RUNTIMES: &[(&str, &str)] = &[
+    ("kubepods", "Kubernetes"),
+    ("/docker/", "Docker"),
+    ("/docker-", "Docker"),
+    ("/libpod", "Podman"),
+    ("/lxc/", "LXC"),
+    ("/lxc.payload", "LXC"),
+];
+
+/// The virtual machine and container rustch runs in, if any. Both can be
+/// set, for a container on a cloud VM.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Virtualization {
+    /// Hypervisor, e.g. `KVM`; `Unknown` when the CPU reports one that we
+    /// cannot name
+    pub vm: Option<String>,
+    /// Container runtime, e.g. `Docker` or `WSL`
+    pub container: Option<String>,
+}
+
+impl Render for Virtualization {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        [("vm", &self.vm), ("container", &self.container)]
+            .into_iter()
+            .filter_map(|(key, value)| Some(Line::keyed(key, value.clone()?)))
+            .collect()
+    }
+}
+
+pub struct VirtualizationModule;
+
+impl InfoModule for VirtualizationModule {
+    fn name(&self) -> &'static str {
+        "virtualization"
+    }
+
+    fn label(&self) -> &'static str {
+        "Virtualization"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        let virtualization = Virtualization {
+            vm: detect_vm(ctx),
+            container: detect_container(ctx),
+        };
+        // Nothing to report on bare metal
+        let detected = virtualization.vm.is_some() || virtualization.container.is_some();
+        field(Some(virtualization).filter(|_| detected))
+    }
+}
+
+/// Checks, like systemd-detect-virt, the clock source the kernel picked,
+/// then the firmware strings, then the hypervisor bit of the CPU flags.
+fn detect_vm(ctx: &Context) -> Option<String> {
+    // Paravirtualized clocks tell KVM apart from QEMU emulation
+    let clocksource = ctx.read_to_string("/sys/devices/system/clocksource/clocksource0/current_clocksource");
+    let clock_vm = match clocksource.as_deref().map(str::trim) {
+        Some("kvm-clock") => Some("KVM"),
+        Some("xen") => Some("Xen"),
+        Some("hyperv_clocksource_tsc_page") => Some("Hyper-V"),
+        _ => None,
+    };
+    if let Some(vm) = clock_vm {
+        return Some(vm.to_string());
+    }
+
+    let dmi_vm = DMI_FILES
+        .iter()
+        .filter_map(|file| ctx.read_to_string(format!("/sys/class/dmi/id/{}", file)))
+        .find_map(|value| {
+            DMI_VENDORS
+                .iter()
+                .find(|(pattern, _)| value.contains(pattern))
+                .map(|(_, name)| name.to_string())
+        });
+    if dmi_vm.is_some() {
+        return dmi_vm;
+    }
+
+    if let Some(hypervisor) = ctx.read_to_string("/sys/hypervisor/type") {
+        if hypervisor.trim() == "xen" {
+            return Some("Xen".to_string());
+        }
+    }
+
+    let cpuinfo = ctx.read_to_string("/proc/cpuinfo")?;
+    let has_hypervisor_flag = cpuinfo
+        .lines()
+        .filter(|line| line.starts_with("flags"))
+        .any(|line| line.split_whitespace().any(|flag| flag == "hypervisor"));
+    has_hypervisor_flag.then(|| "Unknown".to_string())
+}
+
+fn detect_container(ctx: &Context) -> Option<String> {
+    // WSL runs Linux in a utility VM shared by all distributions, which
+    // systemd counts as a container
+    let osrelease = ctx.read_to_string("/proc/sys/kernel/osrelease").unwrap_or_default();
+    if osrelease.to_lowercase().contains("microsoft") {
+        return Some("WSL".to_string());
+    }
+
+    // Set by the container manager for PID 1; /proc/1/environ is only
+    // readable by root
+    let manager = ctx
+        .read_to_string("/run/systemd/container")
+        .map(|content| content.trim().to_string())
+        .or_else(|| {
+            let environ = ctx.read_to_string("/proc/1/environ")?;
+            environ
+                .split('\0')
+                .find_map(|var| var.strip_prefix("container="))
+                .map(str::to_string)
+        })
+        .filter(|manager| !manager.is_empty());
+    if let Some(manager) = manager {
+        let name = CONTAINER_MANAGERS
+            .iter()
+            .find(|(id, _)| *id == manager)
+            .map_or(manager.clone(), |(_, name)| name.to_string());
+        return Some(name);
+    }
+
+    if ctx.exists("/.dockerenv") {
+        return Some("Docker".to_string());
+    }
+    if ctx.exists("/run/.containerenv") {
+        return Some("Podman".to_string());
+    }
+    if ctx.exists("/.flatpak-info") {
+        return Some("Flatpak".to_string());
+    }
+
+    let cgroup = ctx.read_to_string("/proc/1/cgroup").unwrap_or_default();
+    let runtime = CGROUP_RUNTIMES
+        .iter()
+        .find(|(fragment, _)| cgroup.contains(fragment))
+        .map(|(_, name)| name.to_string());
+    if runtime.is_some() {
+        return runtime;
+    }
+
+    // OpenVZ exposes /proc/vz in containers and also /proc/bc on the host
+    (ctx.exists("/proc/vz") && !ctx.exists("/proc/bc")).then(|| "OpenVZ".to_string())
+}
@@ -0,0 +1,170 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use super::de::has_graphical_session;
+use super::process::user_processes;
+use super::{extract_version, field, Context, Field, InfoModule, Line, Render};
+use crate::config::Config;
+use crate::format::format_window_manager;
+
+/// Process names of window managers and compositors, the name shown for
+/// them, and the flag that makes the binary print its version.
+const WINDOW_MANAGERS: &[(&str, &str, Option<&str>)] = &[
+    ("gnome-shell", "Mutter", None),
+    ("mutter", "Mutter", Some("--version")),
+    ("kwin_wayland", "KWin", Some("--version")),
+    ("kwin_x11", "KWin", Some("--version")),
+    ("kwin", "KWin", Some("--version")),
+    ("xfwm4", "Xfwm4", Some("--version")),
+    ("marco", "Marco", Some("--version")),
+    ("muffin", "Muffin", Some("--version")),
+    ("cinnamon", "Muffin", None),
+    ("gala", "Gala", None),
+    ("sway", "Sway", Some("--version")),
+    ("Hyprland", "Hyprland", Some("--version")),
+    ("river", "River", Some("-version")),
+    ("wayfire", "Wayfire", Some("--version")),
+    ("weston", "Weston", Some("--version")),
+    ("labwc", "labwc", Some("--version")),
+    ("niri", "niri", Some("--version")),
+    ("cage", "Cage", None),
+    ("hikari", "Hikari", None),
+    ("openbox", "Openbox", Some("--version")),
+    ("i3", "i3", Some("--version")),
+    ("bspwm", "bspwm", Some("-v")),
+    ("dwm", "dwm", None),
+    ("fluxbox", "Fluxbox", Some("-version")),
+    ("awesome", "awesome", None),
+    ("xmonad", "xmonad", Some("--version")),
+    ("qtile", "qtile", Some("--version")),
+    ("enlightenment", "Enlightenment", None),
+    ("jwm", "JWM", None),
+    ("icewm", "IceWM", Some("--version")),
+    ("fvwm", "FVWM", Some("--version")),
+    ("fvwm3", "FVWM", Some("--version")),
+    ("herbstluftwm", "Herbstluftwm", Some("--version")),
+    ("spectrwm", "Spectrwm", None),
+];
+
+/// The window manager or Wayland compositor and, when its binary reports
+/// one, its version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WindowManager {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl Render for WindowManager {
+    fn render(&self, _config: &Config) -> Vec<Line> {
+        vec![Line::new(format_window_manager(self))]
+    }
+}
+
+pub struct WmModule;
+
+impl InfoModule for WmModule {
+    fn name(&self) -> &'static str {
+        "wm"
+    }
+
+    fn label(&self) -> &'static str {
+        "WM"
+    }
+
+    fn is_live_only(&self) -> bool {
+        true
+    }
+
+    fn is_available(&self, ctx: &Context) -> bool {
+        has_graphical_session(ctx)
+    }
+
+    fn collect(&self, ctx: &Context) -> Option<Box<dyn Field>> {
+        field(get_window_manager(ctx))
+    }
+}
+
+fn get_window_manager(ctx: &Context) -> Option<WindowManager> {
+    // First check environment variables
+    if let Some(wm) = ctx.var("WINDOW_MANAGER").filter(|wm| !wm.is_empty()) {
+        return Some(WindowManager { name: wm, version: None });
+    }
+
+    // The compositor serving our Wayland display, then any window manager
+    // the user runs; names must match exactly, as i3 comes with i3bar and
+    // i3status
+    let processes = user_processes(ctx);
+    let process = wayland_compositor(ctx, &processes).or_else(|| {
+        WINDOW_MANAGERS
+            .iter()
+            .find(|(process, _, _)| processes.iter().any(|(_, comm)| comm == process))
+            .map(|(process, _, _)| process.to_string())
+    });
+    if let Some(process) = process {
+        return Some(match WINDOW_MANAGERS.iter().find(|(name, _, _)| *name == process) {
+            Some((_, name, flag)) => WindowManager {
+                name: name.to_string(),
+                version: flag.and_then(|flag| {
+                    let output = ctx.run(&process, &[flag])?;
+                    output.lines().next().and_then(extract_version)
+                }),
+            },
+            // An unknown compositor, still known to own the display
+            None => WindowManager {
+                name: process,
+                version: None,
+            },
+        });
+    }
+
+    // Without access to the processes, guess from the desktop. We always
+    // see our own process when /proc is readable
+    if !processes.is_empty() {
+        return None;
+    }
+    let desktop = ctx.var("XDG_CURRENT_DESKTOP")?;
+    let name = match desktop.to_lowercase().as_str() {
+        "gnome" | "ubuntu:gnome" => "Mutter",
+        "kde" => "KWin",
+        "xfce" => "Xfwm4",
+        "lxde" | "lxqt" => "Openbox",
+        "mate" => "Marco",
+        "cinnamon" => "Muffin",
+        "pantheon" => "Gala",
+        "budgie:gnome" | "budgie" => "Mutter",
+        _ => return None,
+    };
+    Some(WindowManager {
+        name: name.to_string(),
+        version: None,
+    })
+}
+
+/// The `comm` of the process listening on `$WAYLAND_DISPLAY`'s socket. The
+/// socket's inode comes from `/proc/net/unix` ("Num RefCount Protocol
+/// Flags Type St Inode Path"), and the owner holds a descriptor linking to
+/// `socket:[<inode>]`.
+fn wayland_compositor(ctx: &Context, processes: &[(u32, String)]) -> Option<String> {
+    let display = ctx.var("WAYLAND_DISPLAY").filter(|display| !display.is_empty())?;
+    let socket = if display.starts_with('/') {
+        PathBuf::from(display)
+    } else {
+        PathBuf::from(ctx.var("XDG_RUNTIME_DIR")?).join(display)
+    };
+
+    let unix = ctx.read_to_string("/proc/net/unix")?;
+    let inode = unix.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        (fields.get(7).map(Path::new) == Some(socket.as_path())).then(|| fields[6].to_string())
+    })?;
+    let target = PathBuf::from(format!("socket:[{}]", inode));
+
+    processes.iter().find_map(|(pid, comm)| {
+        let fds = Path::new("/proc").join(pid.to_string()).join("fd");
+        let owns_socket = ctx
+            .read_dir(&fds)?
+            .iter()
+            .any(|fd| ctx.source().read_link(&fds.join(fd)).as_ref() == Some(&target));
+        owns_socket.then(|| comm.clone())
+    })
+}
@@ -0,0 +1,523 @@
+//! Where collectors get their data from. Every file read, environment lookup
+//! and command run by a module goes through a [`SystemSource`], so the same
+//! collectors can inspect the running system, an image mounted with
+//! `--root`, or a test fixture.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::net::Ipv4Addr;
+use std::path::{Component, Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::OnceLock;
+use sysinfo::{DiskExt, RefreshKind, System, SystemExt};
+
+/// Links followed while resolving one path before giving up, as Linux's
+/// `MAXSYMLINKS`.
+const MAX_LINKS: usize = 40;
+
+/// Output of a command run through a [`SystemSource`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stdout: String,
+}
+
+/// Size of a mounted filesystem, as reported by statvfs(3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct FilesystemUsage {
+    pub total_bytes: u64,
+    /// Space available to unprivileged users
+    pub available_bytes: u64,
+}
+
+/// An IPv4 address of a network interface, as reported by getifaddrs(3).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceAddress {
+    pub interface: String,
+    pub address: Ipv4Addr,
+    /// Length of the netmask, 32 for point-to-point links
+    pub prefix: u32,
+}
+
+/// Access to the files, environment and commands of the system being
+/// inspected. Paths are absolute paths on that system (`/etc/os-release`);
+/// [`root`](SystemSource::root) says where they live on this machine.
+pub trait SystemSource: Send + Sync {
+    /// Directory that absolute paths of the inspected system resolve against.
+    fn root(&self) -> &Path;
+
+    /// Whether this describes a running system, so that live-only modules
+    /// (uptime, memory, shell, ...) make sense.
+    fn is_live(&self) -> bool;
+
+    /// Whether this is the machine rustch itself runs on. Only then may
+    /// collectors fall back to sysinfo for values the files do not provide.
+    fn is_host(&self) -> bool;
+
+    /// Operating system family, as in [`std::env::consts::OS`].
+    fn os(&self) -> &str;
+
+    /// Process id of rustch itself, the starting point for process walks.
+    fn pid(&self) -> u32;
+
+    fn var(&self, name: &str) -> Option<String>;
+
+    /// Runs a command and captures its output. Returns `None` if the command
+    /// could not be started.
+    fn run(&self, program: &str, args: &[&str]) -> Option<CommandOutput>;
+
+    /// Size and free space of the filesystem mounted at `mount_point`.
+    fn filesystem_usage(&self, mount_point: &Path) -> Option<FilesystemUsage>;
+
+    /// IPv4 addresses of the network interfaces. Linux has no file that
+    /// ties them to their interface.
+    fn ipv4_addresses(&self) -> Vec<InterfaceAddress>;
+
+    /// Where `path` on the inspected system lives on this machine, taking
+    /// every component literally. Use [`host_path`](SystemSource::host_path)
+    /// unless `path` is known to hold no symbolic links.
+    fn locate(&self, path: &Path) -> PathBuf {
+        self.root().join(path.strip_prefix("/").unwrap_or(path))
+    }
+
+    /// Where `path` on the inspected system lives on this machine, with its
+    /// symbolic links resolved inside the root.
+    fn host_path(&self, path: &Path) -> PathBuf {
+        if self.root() == Path::new("/") {
+            return path.to_path_buf();
+        }
+        self.locate(&resolve_links(path, true, |path| self.locate(path)))
+    }
+
+    fn read_to_string(&self, path: &Path) -> Option<String> {
+        fs::read_to_string(self.host_path(path)).ok()
+    }
+
+    fn read(&self, path: &Path) -> Option<Vec<u8>> {
+        fs::read(self.host_path(path)).ok()
+    }
+
+    /// Names of the entries of a directory, sorted.
+    fn read_dir(&self, path: &Path) -> Option<Vec<String>> {
+        let mut names: Vec<String> = fs::read_dir(self.host_path(path))
+            .ok()?
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        Some(names)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.host_path(path).exists()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.host_path(path).is_dir()
+    }
+
+    /// The target of a symbolic link, as stored in the link.
+    fn read_link(&self, path: &Path) -> Option<PathBuf> {
+        let link = match self.root() == Path::new("/") {
+            true => path.to_path_buf(),
+            false => self.locate(&resolve_links(path, false, |path| self.locate(path))),
+        };
+        fs::read_link(link).ok()
+    }
+}
+
+/// Resolves the symbolic links in `path` against the inspected system, as
+/// chroot(2) would: absolute targets start over at its root, and `..` stops
+/// there. Otherwise a link such as NixOS' `/etc/os-release ->
+/// /etc/static/os-release` would lead to this machine's files. The last
+/// component is only followed with `follow_last`. `locate` gives where a
+/// path without links lives on this machine.
+fn resolve_links(path: &Path, follow_last: bool, locate: impl Fn(&Path) -> PathBuf) -> PathBuf {
+    fn push_components(pending: &mut Vec<PathBuf>, path: &Path) {
+        for component in path.components().rev() {
+            match component {
+                Component::Normal(name) => pending.push(PathBuf::from(name)),
+                Component::ParentDir => pending.push(PathBuf::from("..")),
+                _ => {}
+            }
+        }
+    }
+
+    let mut pending = Vec::new();
+    push_components(&mut pending, path);
+    let mut resolved = PathBuf::from("/");
+    let mut links = 0;
+    while let Some(component) = pending.pop() {
+        if component == Path::new("..") {
+            resolved.pop();
+            continue;
+        }
+
+        let candidate = resolved.join(&component);
+        if !follow_last && pending.is_empty() {
+            resolved = candidate;
+            break;
+        }
+        match fs::read_link(locate(&candidate)) {
+            Ok(target) if links < MAX_LINKS => {
+                links += 1;
+                if target.has_root() {
+                    resolved = PathBuf::from("/");
+                }
+                push_components(&mut pending, &target);
+            }
+            _ => resolved = candidate,
+        }
+    }
+    resolved
+}
+
+/// The real filesystem, environment and commands. With a root other than
+/// `/` this inspects an installed system that is not running.
+#[derive(Debug, Clone)]
+pub struct RealSource {
+    root: PathBuf,
+    filesystems: OnceLock<Vec<(PathBuf, FilesystemUsage)>>,
+}
+
+impl RealSource {
+    pub fn new() -> Self {
+        RealSource::with_root("/")
+    }
+
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        RealSource {
+            root: root.into(),
+            filesystems: OnceLock::new(),
+        }
+    }
+}
+
+impl Default for RealSource {
+    fn default() -> Self {
+        RealSource::new()
+    }
+}
+
+impl SystemSource for RealSource {
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn is_live(&self) -> bool {
+        self.root == Path::new("/")
+    }
+
+    fn is_host(&self) -> bool {
+        self.is_live()
+    }
+
+    fn os(&self) -> &str {
+        env::consts::OS
+    }
+
+    fn pid(&self) -> u32 {
+        process::id()
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        env::var(name).ok()
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> Option<CommandOutput> {
+        let output = Command::new(program).args(args).output().ok()?;
+        Some(CommandOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        })
+    }
+
+    fn filesystem_usage(&self, mount_point: &Path) -> Option<FilesystemUsage> {
+        if !self.is_host() {
+            return None;
+        }
+
+        // sysinfo lists the mounted disks along with their statvfs figures
+        let filesystems = self.filesystems.get_or_init(|| {
+            let sys = System::new_with_specifics(RefreshKind::new().with_disks_list());
+            sys.disks()
+                .iter()
+                .map(|disk| {
+                    let usage = FilesystemUsage {
+                        total_bytes: disk.total_space(),
+                        available_bytes: disk.available_space(),
+                    };
+                    (disk.mount_point().to_path_buf(), usage)
+                })
+                .collect()
+        });
+
+        filesystems
+            .iter()
+            .find(|(path, _)| path == mount_point)
+            .map(|(_, usage)| *usage)
+    }
+
+    #[cfg(unix)]
+    fn ipv4_addresses(&self) -> Vec<InterfaceAddress> {
+        if !self.is_host() {
+            return Vec::new();
+        }
+
+        let Ok(addresses) = nix::ifaddrs::getifaddrs() else {
+            return Vec::new();
+        };
+        addresses
+            .filter_map(|ifaddr| {
+                let address = ifaddr.address?.as_sockaddr_in()?.ip();
+                let netmask = ifaddr.netmask?.as_sockaddr_in()?.ip();
+                Some(InterfaceAddress {
+                    interface: ifaddr.interface_name,
+                    address,
+                    prefix: u32::from(netmask).count_ones(),
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(not(unix))]
+    fn ipv4_addresses(&self) -> Vec<InterfaceAddress> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct FixtureError(String);
+
+impl fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fixture error: {}", self.0)
+    }
+}
+
+impl Error for FixtureError {}
+
+/// A canned system for tests: a directory tree standing in for `/`, plus an
+/// environment and command outputs.
+///
+/// [`load`](FixtureSource::load) reads a fixture directory containing
+/// `root/` and an optional `fixture.toml`:
+///
+/// ```toml
+/// # Inherit files, env and commands from another fixture
+/// base = "../base"
+/// os = "linux"
+/// pid = 4242
+/// live = true
+///
+/// [env]
+/// SHELL = "/bin/bash"
+///
+/// [commands]
+/// "bash --version" = "GNU bash, version 5.2.15(1)-release"
+///
+/// [filesystems]
+/// "/" = { total_bytes = 512110190592, available_bytes = 389011341312 }
+///
+/// [ipv4]
+/// enp5s0 = ["192.168.1.42/24"]
+/// ```
+///
+/// Files are looked up in this fixture's `root/` first, then in the bases'.
+/// Commands without canned output fail to start.
+#[derive(Debug, Clone)]
+pub struct FixtureSource {
+    roots: Vec<PathBuf>,
+    env: HashMap<String, String>,
+    commands: HashMap<String, String>,
+    filesystems: HashMap<PathBuf, FilesystemUsage>,
+    ipv4: Vec<InterfaceAddress>,
+    os: String,
+    pid: u32,
+    live: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureFile {
+    base: Option<PathBuf>,
+    os: Option<String>,
+    pid: Option<u32>,
+    live: Option<bool>,
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(default)]
+    commands: HashMap<String, String>,
+    #[serde(default)]
+    filesystems: HashMap<PathBuf, FilesystemUsage>,
+    #[serde(default)]
+    ipv4: HashMap<String, Vec<String>>,
+}
+
+impl FixtureSource {
+    /// An empty Linux system rooted at `root`.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FixtureSource {
+            roots: vec![root.into()],
+            env: HashMap::new(),
+            commands: HashMap::new(),
+            filesystems: HashMap::new(),
+            ipv4: Vec::new(),
+            os: "linux".to_string(),
+            pid: 4242,
+            live: true,
+        }
+    }
+
+    /// Loads a fixture directory, see the type documentation.
+    pub fn load(dir: &Path) -> Result<Self, FixtureError> {
+        let fixture_path = dir.join("fixture.toml");
+        let fixture: FixtureFile = match fs::read_to_string(&fixture_path) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|e| FixtureError(format!("{}: {}", fixture_path.display(), e)))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => FixtureFile {
+                base: None,
+                os: None,
+                pid: None,
+                live: None,
+                env: HashMap::new(),
+                commands: HashMap::new(),
+                filesystems: HashMap::new(),
+                ipv4: HashMap::new(),
+            },
+            Err(e) => return Err(FixtureError(format!("{}: {}", fixture_path.display(), e))),
+        };
+
+        let mut source = match &fixture.base {
+            Some(base) => FixtureSource::load(&dir.join(base))?,
+            None => FixtureSource {
+                roots: Vec::new(),
+                ..FixtureSource::new(PathBuf::new())
+            },
+        };
+
+        source.roots.insert(0, dir.join("root"));
+        source.env.extend(fixture.env);
+        source.commands.extend(fixture.commands);
+        source.filesystems.extend(fixture.filesystems);
+        for (interface, addresses) in fixture.ipv4 {
+            source.ipv4.retain(|address| address.interface != interface);
+            for cidr in addresses {
+                let parsed = cidr
+                    .split_once('/')
+                    .and_then(|(address, prefix)| Some((address.parse().ok()?, prefix.parse().ok()?)));
+                let Some((address, prefix)) = parsed else {
+                    return Err(FixtureError(format!("{}: invalid IPv4 address '{}'", fixture_path.display(), cidr)));
+                };
+                source.ipv4.push(InterfaceAddress {
+                    interface: interface.clone(),
+                    address,
+                    prefix,
+                });
+            }
+        }
+        if let Some(os) = fixture.os {
+            source.os = os;
+        }
+        if let Some(pid) = fixture.pid {
+            source.pid = pid;
+        }
+        if let Some(live) = fixture.live {
+            source.live = live;
+        }
+
+        Ok(source)
+    }
+
+    pub fn env(mut self, name: &str, value: &str) -> Self {
+        self.env.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Sets the output of `command`, given as the program and its arguments
+    /// joined by spaces.
+    pub fn command(mut self, command: &str, stdout: &str) -> Self {
+        self.commands.insert(command.to_string(), stdout.to_string());
+        self
+    }
+
+    /// Sets the size of the filesystem mounted at `mount_point`.
+    pub fn filesystem(mut self, mount_point: &str, usage: FilesystemUsage) -> Self {
+        self.filesystems.insert(PathBuf::from(mount_point), usage);
+        self
+    }
+}
+
+impl SystemSource for FixtureSource {
+    fn root(&self) -> &Path {
+        &self.roots[0]
+    }
+
+    fn is_live(&self) -> bool {
+        self.live
+    }
+
+    fn is_host(&self) -> bool {
+        false
+    }
+
+    fn os(&self) -> &str {
+        &self.os
+    }
+
+    fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        self.env.get(name).cloned()
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> Option<CommandOutput> {
+        let command = std::iter::once(program).chain(args.iter().copied()).collect::<Vec<_>>().join(" ");
+        self.commands.get(&command).map(|stdout| CommandOutput {
+            success: true,
+            stdout: stdout.clone(),
+        })
+    }
+
+    fn filesystem_usage(&self, mount_point: &Path) -> Option<FilesystemUsage> {
+        self.filesystems.get(mount_point).copied()
+    }
+
+    fn ipv4_addresses(&self) -> Vec<InterfaceAddress> {
+        self.ipv4.clone()
+    }
+
+    /// The path in the first root that has it, so fixtures can override
+    /// files of their bases.
+    fn locate(&self, path: &Path) -> PathBuf {
+        let relative = path.strip_prefix("/").unwrap_or(path);
+        self.roots
+            .iter()
+            .map(|root| root.join(relative))
+            .find(|candidate| candidate.symlink_metadata().is_ok())
+            .unwrap_or_else(|| self.roots[0].join(relative))
+    }
+
+    fn read_dir(&self, path: &Path) -> Option<Vec<String>> {
+        let resolved = resolve_links(path, true, |path| self.locate(path));
+        let relative = resolved.strip_prefix("/").unwrap_or(&resolved);
+        let mut found = false;
+        let mut names = Vec::new();
+        for root in &self.roots {
+            if let Ok(entries) = fs::read_dir(root.join(relative)) {
+                found = true;
+                names.extend(entries.flatten().map(|entry| entry.file_name().to_string_lossy().into_owned()));
+            }
+        }
+        names.sort();
+        names.dedup();
+        found.then_some(names)
+    }
+}
@@ -0,0 +1,247 @@
+//! Just enough of the SQLite file format to count the rows of a table. Used
+//! to read package databases (rpm, FreeBSD pkg) without linking libsqlite3.
+//!
+//! Only the main database file is read. While a `-wal` file holds changes
+//! that were not checkpointed into it yet, the count would be stale, so
+//! such databases are not read at all.
+
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const HEADER_MAGIC: &[u8] = b"SQLite format 3\0";
+const MAX_DEPTH: usize = 32;
+
+/// A write-ahead log is a 32-byte header followed by frames of changed
+/// pages.
+const WAL_HEADER_SIZE: u64 = 32;
+
+const INTERIOR_TABLE: u8 = 0x05;
+const LEAF_TABLE: u8 = 0x0d;
+
+/// Counts the rows of `table` in the SQLite database at `path`. Returns
+/// `None` if the file is not a SQLite database, has no such table or has
+/// changes pending in its write-ahead log.
+pub fn count_rows(path: &Path, table: &str) -> Option<usize> {
+    if has_pending_wal(path) {
+        return None;
+    }
+
+    let mut db = Database::open(path)?;
+    let root = db.find_table(table)?;
+    db.count_rows(root, 0)
+}
+
+/// SQLite checkpoints the log and deletes it when the last connection
+/// closes, so frames left in it are changes the main file lacks.
+fn has_pending_wal(path: &Path) -> bool {
+    let mut wal = path.as_os_str().to_owned();
+    wal.push("-wal");
+    fs::metadata(wal).is_ok_and(|metadata| metadata.len() > WAL_HEADER_SIZE)
+}
+
+struct Database {
+    file: File,
+    page_size: u64,
+    usable_size: usize,
+}
+
+impl Database {
+    fn open(path: &Path) -> Option<Self> {
+        let mut file = File::open(path).ok()?;
+        let mut header = [0u8; 100];
+        file.read_exact(&mut header).ok()?;
+        if &header[..16] != HEADER_MAGIC {
+            return None;
+        }
+
+        // A stored page size of 1 means 65536
+        let page_size = match u16::from_be_bytes([header[16], header[17]]) {
+            1 => 65536,
+            size => size as u64,
+        };
+        let reserved = header[20] as usize;
+
+        Some(Database {
+            file,
+            page_size,
+            usable_size: (page_size as usize).checked_sub(reserved)?,
+        })
+    }
+
+    fn page(&mut self, number: u32) -> Option<Vec<u8>> {
+        if number == 0 {
+            return None;
+        }
+        let mut page = vec![0u8; self.page_size as usize];
+        self.file.seek(SeekFrom::Start((number as u64 - 1) * self.page_size)).ok()?;
+        self.file.read_exact(&mut page).ok()?;
+        Some(page)
+    }
+
+    /// Looks `table` up in `sqlite_master` and returns its root page.
+    fn find_table(&mut self, table: &str) -> Option<u32> {
+        let mut found = None;
+        self.walk_leaves(1, 0, &mut |db, page, header_offset| {
+            for cell in cell_offsets(page, header_offset, 8)? {
+                let record = db.leaf_payload(page, cell)?;
+                let columns = parse_record(record)?;
+                if let [Column::Text(kind), Column::Text(name), _, Column::Integer(root), ..] = columns.as_slice() {
+                    if kind == "table" && name.eq_ignore_ascii_case(table) {
+                        found = u32::try_from(*root).ok();
+                    }
+                }
+            }
+            Some(())
+        })?;
+        found
+    }
+
+    fn count_rows(&mut self, root: u32, depth: usize) -> Option<usize> {
+        let mut count = 0;
+        self.walk_leaves(root, depth, &mut |_, page, header_offset| {
+            count += read_u16(page, header_offset + 3)? as usize;
+            Some(())
+        })?;
+        Some(count)
+    }
+
+    /// Calls `visit` with every leaf page of the table b-tree rooted at
+    /// `number`, along with the offset of the page's b-tree header.
+    fn walk_leaves<F>(&mut self, number: u32, depth: usize, visit: &mut F) -> Option<()>
+    where
+        F: FnMut(&Database, &[u8], usize) -> Option<()>,
+    {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        let page = self.page(number)?;
+        // Page 1 starts with the 100-byte database header
+        let header_offset = if number == 1 { 100 } else { 0 };
+
+        match *page.get(header_offset)? {
+            LEAF_TABLE => visit(self, &page, header_offset),
+            INTERIOR_TABLE => {
+                let mut children = Vec::new();
+                for cell in cell_offsets(&page, header_offset, 12)? {
+                    children.push(read_u32(&page, cell)?);
+                }
+                children.push(read_u32(&page, header_offset + 8)?);
+
+                for child in children {
+                    self.walk_leaves(child, depth + 1, visit)?;
+                }
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the part of a table leaf cell's payload stored on the page.
+    fn leaf_payload<'a>(&self, page: &'a [u8], cell: usize) -> Option<&'a [u8]> {
+        let (payload_size, len) = read_varint(page.get(cell..)?)?;
+        let (_, rowid_len) = read_varint(page.get(cell + len..)?)?;
+        let start = cell + len + rowid_len;
+
+        let usable = self.usable_size;
+        let payload_size = payload_size as usize;
+        let max_local = usable.checked_sub(35)?;
+        let local = if payload_size <= max_local {
+            payload_size
+        } else {
+            let min_local = ((usable - 12) * 32 / 255).checked_sub(23)?;
+            let k = min_local + (payload_size - min_local) % (usable - 4);
+            if k <= max_local { k } else { min_local }
+        };
+
+        page.get(start..start + local)
+    }
+}
+
+fn cell_offsets(page: &[u8], header_offset: usize, header_size: usize) -> Option<Vec<usize>> {
+    let cells = read_u16(page, header_offset + 3)? as usize;
+    let pointers = header_offset + header_size;
+    (0..cells)
+        .map(|i| read_u16(page, pointers + i * 2).map(|offset| offset as usize))
+        .collect()
+}
+
+enum Column {
+    Null,
+    Integer(i64),
+    Text(String),
+    Other,
+}
+
+/// Parses a record, stopping at the first column that is not stored
+/// locally.
+fn parse_record(record: &[u8]) -> Option<Vec<Column>> {
+    let (header_size, mut pos) = read_varint(record)?;
+    let header_size = header_size as usize;
+    let mut serial_types = Vec::new();
+    while pos < header_size {
+        let (serial_type, len) = read_varint(record.get(pos..)?)?;
+        serial_types.push(serial_type);
+        pos += len;
+    }
+
+    let mut columns = Vec::new();
+    let mut body = header_size;
+    for serial_type in serial_types {
+        let size = match serial_type {
+            0 | 8 | 9 => 0,
+            1..=4 => serial_type as usize,
+            5 => 6,
+            6 | 7 => 8,
+            n if n >= 12 => ((n - 12) / 2) as usize,
+            _ => return None,
+        };
+        let Some(bytes) = record.get(body..body + size) else {
+            break;
+        };
+        body += size;
+
+        columns.push(match serial_type {
+            0 => Column::Null,
+            8 => Column::Integer(0),
+            9 => Column::Integer(1),
+            1..=6 => {
+                // Big-endian two's complement of `size` bytes
+                let mut value: i64 = if bytes[0] & 0x80 != 0 { -1 } else { 0 };
+                for &byte in bytes {
+                    value = (value << 8) | byte as i64;
+                }
+                Column::Integer(value)
+            }
+            n if n >= 13 && n % 2 == 1 => Column::Text(String::from_utf8_lossy(bytes).into_owned()),
+            _ => Column::Other,
+        });
+    }
+
+    Some(columns)
+}
+
+/// Reads a SQLite varint, returning the value and its length in bytes.
+fn read_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value: u64 = 0;
+    for i in 0..9 {
+        let byte = *bytes.get(i)?;
+        if i == 8 {
+            return Some(((value << 8) | byte as u64, 9));
+        }
+        value = (value << 7) | (byte & 0x7f) as u64;
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(offset..offset + 4)?.try_into().ok()?))
+}
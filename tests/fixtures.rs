@@ -0,0 +1,342 @@
+//! Renders the full output for canned systems under `tests/fixtures` and
+//! compares it with each fixture's `expected.txt`.
+//!
+//! Run with `RUSTCH_BLESS=1` to write the current output as the expected one
+//! after an intended change, then review the diff.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustch::config::{self, Config};
+use rustch::display::{build_json, get_info_lines, render_output, strip_ansi_codes};
+use rustch::distro::{detect_distribution_from, logo_distribution_from};
+use rustch::modules::{self, Context, Cpu, Font, Memory, Monitors, Packages, WindowManager};
+use rustch::{get_ascii_art, get_theme_colors, FixtureSource, RealSource, SystemInfo, SystemSource};
+
+fn fixture_dir(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+fn collect(name: &str) -> (String, Context, SystemInfo) {
+    collect_with(name, Config::default())
+}
+
+fn collect_with(name: &str, config: Config) -> (String, Context, SystemInfo) {
+    let source = FixtureSource::load(&fixture_dir(name)).expect("fixture should load");
+    let distro = detect_distribution_from(&source).expect("detection should not fail");
+    let ctx = Context::new(config).with_source(source);
+    let info = SystemInfo::collect(&ctx, &modules::registry());
+    (distro, ctx, info)
+}
+
+/// The uncolored text output of `rustch --all`.
+fn render(name: &str) -> String {
+    let (distro, ctx, info) = collect(name);
+    let layout = config::layout(&modules::names());
+    let info_lines = get_info_lines(&info, &layout, &ctx.config, false);
+    let logo = logo_distribution_from(ctx.source(), &distro);
+    render_output(&get_ascii_art(&logo), &info_lines, &get_theme_colors(&logo), true)
+}
+
+fn check(name: &str) {
+    let actual = render(name);
+    let expected_path = fixture_dir(name).join("expected.txt");
+
+    if env::var_os("RUSTCH_BLESS").is_some() {
+        fs::write(&expected_path, &actual).expect("expected output should be writable");
+        return;
+    }
+
+    let expected = fs::read_to_string(&expected_path)
+        .unwrap_or_else(|e| panic!("{}: {} (run with RUSTCH_BLESS=1 to create it)", expected_path.display(), e));
+    assert_eq!(actual, expected, "output for fixture '{}' changed", name);
+}
+
+macro_rules! fixtures {
+    ($($name:ident),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                check(stringify!($name));
+            }
+        )*
+    };
+}
+
+fixtures!(
+    alpine, arch, centos, debian, deepin, elementary, endeavouros, fedora, freebsd, garuda, gentoo, kali,
+    macos, manjaro, mint, netbsd, nixos, openbsd, opensuse, pop, rhel, rocky, slackware, solus, ubuntu, void,
+    zorin, image,
+);
+
+#[test]
+fn json_output() {
+    let (distro, _, info) = collect("fedora");
+    let layout = config::layout(&["os", "packages", "memory", "cpu", "uptime"]);
+    let json = build_json(&distro, &info, &layout);
+
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "distro": "fedora",
+            "os": "Fedora Linux 40 (Workstation Edition)",
+            "packages": [
+                { "manager": "rpm", "count": 9 },
+                { "manager": "flatpak", "count": 1 },
+            ],
+            "memory": { "used_bytes": 5972705280u64, "total_bytes": 16710123520u64, "host_total_bytes": null },
+            "cpu": {
+                "brand": "12th Gen Intel Core i7-12700H",
+                "logical_cores": 20,
+                "physical_cores": 14,
+                "sockets": 1,
+                "performance_cores": 6,
+                "efficiency_cores": 8,
+                "max_frequency_mhz": 4700,
+                "temperature_celsius": 52.0,
+                "cpu_limit": null,
+            },
+            "uptime": 93784,
+        })
+    );
+    // Keys follow the layout, not the alphabet
+    let keys: Vec<&str> = json.as_object().expect("an object").keys().map(String::as_str).collect();
+    assert_eq!(keys, ["distro", "os", "packages", "memory", "cpu", "uptime"]);
+}
+
+#[test]
+fn json_keeps_derivative_id() {
+    // Rocky has no logo of its own and borrows RHEL's, but is still Rocky
+    let (distro, ctx, info) = collect("rocky");
+    let json = build_json(&distro, &info, &config::layout(&["os"]));
+
+    assert_eq!(json["distro"], "rocky");
+    assert_eq!(logo_distribution_from(ctx.source(), &distro), "rhel");
+}
+
+#[test]
+fn image_skips_live_only_modules() {
+    let (_, _, info) = collect("image");
+
+    assert_eq!(info.get_as::<String>("host").map(String::as_str), Some("buildbox"));
+    for name in ["kernel", "uptime", "shell", "de", "wm", "terminal", "cpu", "memory"] {
+        assert!(info.entry(name).is_none(), "{} should be skipped", name);
+    }
+}
+
+#[test]
+fn root_resolves_symlinks_inside_it() {
+    // NixOS links /etc/os-release to /etc/static/os-release, which is the
+    // image's file and not this machine's
+    let root = fixture_dir("nixos").join("root");
+    let source = RealSource::with_root(&root);
+    assert_eq!(detect_distribution_from(&source).expect("detection should not fail"), "nixos");
+    assert_eq!(source.read_link(Path::new("/etc/os-release")), Some(PathBuf::from("/etc/static/os-release")));
+    assert_eq!(source.host_path(Path::new("/../../etc/os-release")), root.join("etc/static/os-release"));
+}
+
+#[test]
+fn disk_lists_real_filesystems() {
+    let config = Config::parse("[disk]\nmounts = []\nbar = true\n").expect("config should parse");
+    let (_, ctx, info) = collect_with("base", config);
+    let lines: Vec<String> = get_info_lines(&info, &config::layout(&["disk"]), &ctx.config, false)
+        .iter()
+        .map(|line| strip_ansi_codes(line))
+        .collect();
+
+    assert_eq!(
+        lines,
+        [
+            "Disk (/): 121.6GB / 467.9GB (25%) - ext4 [███░░░░░░░]",
+            "Disk (/boot/efi): 12.7MB / 511.0MB (2%) - vfat [░░░░░░░░░░]",
+            "Disk (/mnt/usb drive): 54.9GB / 59.6GB (92%) - exfat [█████████░]",
+        ]
+    );
+}
+
+fn package_count(name: &str, manager: &str) -> Option<usize> {
+    let (_, _, info) = collect(name);
+    let packages = info.get_as::<Packages>("packages")?;
+    packages.0.iter().find(|package| package.manager == manager).map(|package| package.count)
+}
+
+#[test]
+fn sqlite_interior_and_overflow_pages() {
+    // 1500 rows three b-tree levels deep on 512-byte pages, some of them and
+    // one schema entry spilling into overflow pages
+    assert_eq!(package_count("rpmdb-large", "rpm"), Some(1500));
+}
+
+#[test]
+fn sqlite_pending_wal_falls_back_to_command() {
+    // The main file has 3 packages and the write-ahead log 3 more
+    assert_eq!(package_count("pkg-wal", "pkg"), Some(6));
+    assert_eq!(package_count("freebsd", "pkg"), Some(4));
+}
+
+#[test]
+fn wm_guessed_from_desktop_only_without_processes() {
+    let wm = |name: &str| {
+        let (_, _, info) = collect(name);
+        info.get_as::<WindowManager>("wm").map(|wm| wm.name.clone())
+    };
+
+    // base lists no processes; ubuntu runs gnome-shell; wm-none sees its own
+    // process and nothing else, so XDG_CURRENT_DESKTOP=GNOME is not trusted
+    assert_eq!(wm("base").as_deref(), Some("Mutter"));
+    assert_eq!(wm("ubuntu").as_deref(), Some("Mutter"));
+    assert_eq!(wm("wm-none"), None);
+}
+
+#[test]
+fn cpu_brand_not_taken_from_arm_board_model() {
+    let brand = |name: &str| {
+        let (_, _, info) = collect(name);
+        info.get_as::<Cpu>("cpu").map(|cpu| cpu.brand.clone())
+    };
+
+    assert_eq!(brand("base").as_deref(), Some("AMD Ryzen 5 5600X 6-Core Processor"));
+    // The Pi's cpuinfo has no model name; only sysinfo on the host itself
+    // could name its Cortex-A72
+    assert_eq!(brand("raspberrypi"), None);
+}
+
+#[test]
+fn memory_formulas() {
+    let used = |formula: &str| {
+        let config = Config::parse(&format!("[memory]\nused = \"{}\"\n", formula)).expect("config should parse");
+        let (_, _, info) = collect_with("base", config);
+        info.get_as::<Memory>("memory").map(|memory| memory.used_bytes)
+    };
+
+    // MemTotal - MemAvailable
+    assert_eq!(used("available"), Some(5_972_705_280));
+    // MemTotal - MemFree - Buffers - (Cached + SReclaimable - Shmem)
+    assert_eq!(used("htop"), Some(5_229_199_360));
+    // sysinfo only describes the machine running the tests
+    assert_eq!(used("sysinfo"), None);
+}
+
+#[test]
+fn network_interfaces_and_hidden_addresses() {
+    let lines = |toml: &str| {
+        let config = Config::parse(toml).expect("config should parse");
+        let (_, ctx, info) = collect_with("base", config);
+        get_info_lines(&info, &config::layout(&["network"]), &ctx.config, false)
+            .iter()
+            .map(|line| strip_ansi_codes(line))
+            .collect::<Vec<_>>()
+    };
+
+    // Virtual interfaces only when named; wlp4s0 is down, and the
+    // WireGuard tunnel has a /32 without a route of its own
+    assert_eq!(
+        lines("[network]\ninterfaces = [\"docker0\", \"wlp4s0\", \"wg0\"]\n"),
+        [
+            "Network (docker0): 172.17.0.1/16 (Virtual, 10 Gbps)",
+            "Network (wg0): 10.8.0.2/32 (Virtual)"
+        ]
+    );
+    assert_eq!(lines("[network]\naddresses = false\n"), ["Network (enp5s0): Wired, 1 Gbps"]);
+}
+
+#[test]
+fn display_reads_edid() {
+    let (_, _, info) = collect("base");
+    let monitors = info.get_as::<Monitors>("display").expect("base has a monitor");
+    let [monitor] = monitors.0.as_slice() else {
+        panic!("only DP-1 is connected: {:?}", monitors);
+    };
+
+    assert_eq!(monitor.manufacturer.as_deref(), Some("Dell"));
+    assert_eq!(monitor.model.as_deref(), Some("DELL U2720Q"));
+    assert_eq!((monitor.width_mm, monitor.height_mm), (Some(597), Some(336)));
+    let preferred = monitor.preferred.expect("EDID has a detailed timing");
+    assert_eq!((preferred.width, preferred.height), (3840, 2160));
+    assert!((preferred.refresh_hz.expect("timing has a clock") - 60.0).abs() < 0.01);
+}
+
+#[test]
+fn terminal_without_tmux_client() {
+    // The query fails when tmux runs on another socket; the server's parent
+    // is init, so the leftover variables are all there is
+    let source = FixtureSource::load(&fixture_dir("ubuntu"))
+        .expect("fixture should load")
+        .command("tmux display-message -p #{client_pid}", "no server running on /tmp/tmux-0/default\n");
+    let ctx = Context::new(Config::default()).with_source(source);
+    let info = SystemInfo::collect(&ctx, &[modules::find("terminal").expect("module exists")]);
+    assert_eq!(info.get_as::<String>("terminal").map(String::as_str), Some("kitty"));
+}
+
+#[test]
+fn terminal_fonts() {
+    // Each terminal's own variables, on top of base's TERM=xterm-kitty
+    let font = |env: &[(&str, &str)]| {
+        let mut source = FixtureSource::load(&fixture_dir("base")).expect("fixture should load");
+        for (name, value) in env {
+            source = source.env(name, value);
+        }
+        let ctx = Context::new(Config::default()).with_source(source);
+        let module = modules::find("terminal_font").expect("module exists");
+        let info = SystemInfo::collect(&ctx, &[module]);
+        get_info_lines(&info, &config::layout(&["terminal_font"]), &ctx.config, false)
+            .iter()
+            .map(|line| strip_ansi_codes(line))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(font(&[("KITTY_WINDOW_ID", "1")]), ["Terminal Font: JetBrains Mono (11.5pt)"]);
+    assert_eq!(
+        font(&[
+            ("TERM", "alacritty"),
+            ("ALACRITTY_SOCKET", "/run/user/1000/Alacritty-wayland-0-2718.sock"),
+            ("ALACRITTY_WINDOW_ID", "94558152843264"),
+        ]),
+        ["Terminal Font: Fira Code (10pt)"]
+    );
+    // foot sets nothing but TERM
+    assert_eq!(font(&[("TERM", "foot")]), ["Terminal Font: Iosevka Term (12pt)"]);
+    assert_eq!(
+        font(&[
+            ("TERM", "xterm-256color"),
+            ("TERM_PROGRAM", "WezTerm"),
+            ("TERM_PROGRAM_VERSION", "20240203-110809-5046fc22"),
+            ("WEZTERM_PANE", "0"),
+        ]),
+        ["Terminal Font: Cascadia Code (13pt)"]
+    );
+    assert_eq!(
+        font(&[
+            ("TERM", "xterm-256color"),
+            ("VTE_VERSION", "7600"),
+            ("GNOME_TERMINAL_SCREEN", "/org/gnome/Terminal/screen/5c3bd3b2_b2c8_4f4e_9a46_0e1a3d1c4f3d"),
+        ]),
+        ["Terminal Font: Fira Code (12pt)"]
+    );
+    let konsole = [("TERM", "xterm-256color"), ("KONSOLE_VERSION", "230805"), ("KONSOLE_DBUS_WINDOW", "/Windows/1")];
+    assert_eq!(font(&konsole), ["Terminal Font: Hack (10pt)"]);
+    assert_eq!(
+        font(&[konsole.as_slice(), &[("XDG_DATA_HOME", "/home/user/data")]].concat()),
+        ["Terminal Font: Noto Sans Mono (11pt)"]
+    );
+}
+
+#[test]
+fn xfce_terminal_uses_xfce_monospace_font() {
+    let font = |source: FixtureSource| {
+        let ctx = Context::new(Config::default()).with_source(source);
+        let info = SystemInfo::collect(&ctx, &[modules::find("terminal_font").expect("module exists")]);
+        info.get_as::<Font>("terminal_font").cloned()
+    };
+    let source = FixtureSource::load(&fixture_dir("xfce")).expect("fixture should load");
+
+    let expected = Font {
+        family: "Iosevka Term".to_string(),
+        size: Some(11.0),
+    };
+    assert_eq!(font(source.clone()), Some(expected));
+    // Never GNOME's monospace font from base's gsettings
+    let unset = source.command("xfconf-query -c xsettings -p /Gtk/MonospaceFontName", "");
+    assert_eq!(font(unset), None);
+}
@@ -0,0 +1,9 @@
+local wezterm = require 'wezterm'
+local config = wezterm.config_builder()
+
+-- config.font = wezterm.font 'Menlo'
+config.font = wezterm.font_with_fallback { 'Cascadia Code', 'Noto Color Emoji' }
+config.font_size = 13.0
+config.color_scheme = 'Tokyo Night'
+
+return config
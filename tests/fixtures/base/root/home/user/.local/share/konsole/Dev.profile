@@ -0,0 +1,7 @@
+[Appearance]
+ColorScheme=Breeze
+Font=Hack,10,-1,5,50,0,0,0,0,0
+
+[General]
+Name=Dev
+Parent=FALLBACK/
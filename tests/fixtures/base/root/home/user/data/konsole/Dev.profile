@@ -0,0 +1,7 @@
+[Appearance]
+ColorScheme=Breeze
+Font=Noto Sans Mono,11,-1,5,50,0,0,0,0,0
+
+[General]
+Name=Dev
+Parent=FALLBACK/